@@ -0,0 +1,285 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anyhow::{anyhow, bail, Result};
+use stead_rent::state::*;
+
+use crate::rpc::RpcClient;
+
+/// Fetches and deserializes a program account
+pub fn fetch<T: AccountDeserialize>(rpc: &RpcClient, address: &Pubkey) -> Result<T> {
+    let data = rpc
+        .get_account_data(address)?
+        .ok_or_else(|| anyhow!("Account {} does not exist", address))?;
+    decode(address, &data)
+}
+
+/// Deserializes the data of a program account, checking its discriminator
+pub fn decode<T: AccountDeserialize>(address: &Pubkey, data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..])
+        .map_err(|_| anyhow!("Account {} has an unexpected type", address))
+}
+
+/// Fetches every account of the program of the given type
+pub fn list<T: AccountDeserialize + Discriminator>(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+) -> Result<Vec<(Pubkey, T)>> {
+    rpc.get_program_accounts(program_id, &T::discriminator())?
+        .into_iter()
+        .map(|(address, data)| Ok((address, decode(&address, &data)?)))
+        .collect()
+}
+
+/// Prints any state, exhibition, item or proposal account
+pub fn inspect(rpc: &RpcClient, address: &Pubkey) -> Result<()> {
+    let data = rpc
+        .get_account_data(address)?
+        .ok_or_else(|| anyhow!("Account {} does not exist", address))?;
+    let discriminator = data.get(..8).unwrap_or_default();
+
+    if discriminator == State::discriminator() {
+        let state = State::try_deserialize(&mut data.as_slice())?;
+        print_state(address, &state);
+    } else if discriminator == Exhibition::discriminator() {
+        let exhibition = Exhibition::try_deserialize(&mut data.as_slice())?;
+        print_exhibition(address, &exhibition);
+    } else if discriminator == ExhibitionItem::discriminator() {
+        let item = ExhibitionItem::try_deserialize(&mut data.as_slice())?;
+        print_item(address, &item);
+    } else {
+        bail!(
+            "Account {} is not a state, an exhibition or an item",
+            address
+        );
+    }
+
+    Ok(())
+}
+
+pub fn print_state(address: &Pubkey, state: &State) {
+    println!("State {}", address);
+    println!("  fee earner:      {}", state.fee_earner);
+    println!("  fee amount:      {} bps", state.fee_amount);
+    println!("  referral fee:    {} bps", state.referral_fee);
+    println!("  min fee:         {} lamports", state.min_fee);
+    if let Some(max_fee) = state.max_fee {
+        println!("  max fee:         {} lamports", max_fee);
+    }
+    for share in &state.fee_recipients {
+        println!(
+            "  fee recipient:   {} bps to {}",
+            share.basis_points, share.recipient
+        );
+    }
+    println!("  price oracle:    {}", state.price_oracle);
+    println!("  max price age:   {} s", state.max_price_age);
+    println!("  max confidence:  {} bps", state.max_confidence);
+    for admin in &state.admins {
+        println!("  admin:           {}", admin);
+    }
+    if state.is_governed() {
+        println!("  threshold:       {}", state.threshold);
+        println!("  timelock:        {} s", state.timelock);
+        println!("  proposals:       {}", state.n_proposals);
+    }
+    println!("  paused:          {}", state.paused);
+    for collection in &state.allowed_collections {
+        println!("  collection:      {}", collection);
+    }
+    print_stats(&state.stats);
+}
+
+pub fn print_exhibition(address: &Pubkey, exhibition: &Exhibition) {
+    let status = match exhibition.status {
+        ExhibitionStatus::Proposed => "proposed",
+        ExhibitionStatus::Active => "active",
+        ExhibitionStatus::Cancelled => "cancelled",
+    };
+
+    println!("Exhibition {}", address);
+    println!(
+        "  property:        {} (room {})",
+        exhibition.property, exhibition.index
+    );
+    println!("  renter:          {}", exhibition.renter);
+    if exhibition.is_group_show() {
+        println!("  exhibitor:       group show");
+    } else {
+        println!("  exhibitor:       {}", exhibition.exhibitor);
+    }
+    println!("  status:          {}", status);
+    println!("  renter fee:      {} bps", exhibition.renter_fee);
+    for share in &exhibition.shares {
+        println!(
+            "  share:           {} bps to {}",
+            share.basis_points, share.recipient
+        );
+    }
+    println!(
+        "  pieces:          {} ({} pending)",
+        exhibition.n_pieces, exhibition.n_pending
+    );
+    println!("  curated:         {}", exhibition.curated);
+    println!("  notice period:   {} s", exhibition.notice_period);
+    if exhibition.status == ExhibitionStatus::Cancelled {
+        println!("  cancelled at:    {}", exhibition.cancelled_at);
+    }
+    print_stats(&exhibition.stats);
+}
+
+pub fn print_item(address: &Pubkey, item: &ExhibitionItem) {
+    let currency = match item.currency {
+        Currency::Lamports => "lamports",
+        Currency::UsdCents => "USD cents",
+    };
+    let status = match item.status {
+        ItemStatus::Pending => "pending",
+        ItemStatus::Listed => "listed",
+    };
+
+    println!("Item {}", address);
+    println!("  exhibition:      {}", item.exhibition);
+    println!("  mint:            {}", item.mint);
+    println!("  seller:          {}", item.seller);
+    println!("  price:           {} {}", item.price, currency);
+    println!("  status:          {}", status);
+    println!("  caption:         {}", item.metadata.caption);
+    println!("  position:        {}", item.metadata.position);
+    if let Some(uri) = &item.metadata.uri {
+        println!("  uri:             {}", uri);
+    }
+    if let Some(bundle) = &item.bundle {
+        println!("  bundle:          {}", bundle);
+    }
+}
+
+pub fn print_proposal(address: &Pubkey, proposal: &Proposal) {
+    println!("Proposal {} (#{})", address, proposal.index);
+    println!("  proposer:        {}", proposal.proposer);
+    match &proposal.action {
+        ProposalAction::SetFees {
+            fee_amount,
+            referral_fee,
+            min_fee,
+            max_fee,
+        } => {
+            println!(
+                "  action:          set fees to {} bps, {} bps for referrals",
+                fee_amount, referral_fee
+            );
+            println!("                   at least {} lamports", min_fee);
+            if let Some(max_fee) = max_fee {
+                println!("                   at most {} lamports", max_fee);
+            }
+        }
+        ProposalAction::SetFeeEarner { fee_earner } => {
+            println!("  action:          set fee earner to {}", fee_earner)
+        }
+        ProposalAction::SetFeeRecipients { recipients } => {
+            println!(
+                "  action:          split fees between {} wallets",
+                recipients.len()
+            );
+            for share in recipients {
+                println!(
+                    "                   {} bps to {}",
+                    share.basis_points, share.recipient
+                );
+            }
+        }
+        ProposalAction::SetPriceOracle {
+            price_oracle,
+            max_price_age,
+            max_confidence,
+        } => println!(
+            "  action:          set price oracle to {}, {} s old at most, {} bps wide at most",
+            price_oracle, max_price_age, max_confidence
+        ),
+        ProposalAction::SetPaused { paused: true } => println!("  action:          pause"),
+        ProposalAction::SetPaused { paused: false } => println!("  action:          resume"),
+        ProposalAction::SetAllowedCollections { collections } if collections.is_empty() => {
+            println!("  action:          allow every collection")
+        }
+        ProposalAction::SetAllowedCollections { collections } => {
+            println!("  action:          allow {} collections", collections.len());
+            for collection in collections {
+                println!("                   {}", collection);
+            }
+        }
+        ProposalAction::SetAdmins {
+            admins,
+            threshold,
+            timelock,
+        } => {
+            println!(
+                "  action:          set {} of {} admins, with a {} s timelock",
+                threshold,
+                admins.len(),
+                timelock
+            );
+            for admin in admins {
+                println!("                   {}", admin);
+            }
+        }
+    }
+    for approval in &proposal.approvals {
+        println!("  approved by:     {}", approval);
+    }
+    if let Some(executable_at) = proposal.executable_at {
+        println!("  executable at:   {}", executable_at);
+    }
+    println!("  expires at:      {}", proposal.expires_at());
+}
+
+fn print_stats(stats: &SalesStats) {
+    println!(
+        "  history:         {} deposited, {} sold, {} withdrawn",
+        stats.pieces_deposited, stats.pieces_sold, stats.pieces_withdrawn
+    );
+    println!("  volume:          {} lamports", stats.volume);
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::AccountSerialize;
+
+    use super::*;
+
+    fn proposal() -> Proposal {
+        Proposal {
+            index: 3,
+            bump: 254,
+            proposer: Pubkey::new_unique(),
+            action: ProposalAction::SetPaused { paused: true },
+            approvals: vec![Pubkey::new_unique()],
+            executable_at: Some(1_000),
+            created_at: 500,
+        }
+    }
+
+    #[test]
+    fn decodes_program_accounts() {
+        let proposal = proposal();
+        let mut data = vec![];
+        proposal.try_serialize(&mut data).unwrap();
+        // Accounts are allocated with room to spare
+        data.resize(Proposal::LEN, 0);
+
+        let decoded: Proposal = decode(&Pubkey::new_unique(), &data).unwrap();
+        assert_eq!(decoded.index, proposal.index);
+        assert_eq!(decoded.proposer, proposal.proposer);
+        assert!(decoded.action == proposal.action);
+        assert_eq!(decoded.approvals, proposal.approvals);
+        assert_eq!(decoded.executable_at, proposal.executable_at);
+        assert_eq!(decoded.expires_at(), 1_000 + Proposal::LIFETIME);
+    }
+
+    #[test]
+    fn rejects_other_account_types() {
+        let mut data = vec![];
+        proposal().try_serialize(&mut data).unwrap();
+        assert!(decode::<State>(&Pubkey::new_unique(), &data).is_err());
+        assert!(decode::<Proposal>(&Pubkey::new_unique(), &data[..20]).is_err());
+        assert!(decode::<Proposal>(&Pubkey::new_unique(), &[]).is_err());
+    }
+}
@@ -0,0 +1,592 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::Hash;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::{nonce, system_program, sysvar};
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token;
+use anyhow::{anyhow, bail, Result};
+use spl_associated_token_account::create_associated_token_account;
+use stead_rent::instructions::{
+    token_metadata, BuyTokenSeedBumps, DepositTokenSeedBumps, InitExhibitionBumpSeeds,
+    WithdrawTokenSeedBumps,
+};
+use stead_rent::state::*;
+
+use crate::accounts::fetch;
+use crate::config::Config;
+use crate::keypair::Keypair;
+use crate::rpc::RpcClient;
+use crate::transaction::{Encoding, Transaction};
+
+/// Builds, signs and sends the instructions of a command
+pub struct Client {
+    pub config: Config,
+    pub rpc: RpcClient,
+
+    /// The wallet signing the transactions, unless they are only built
+    pub wallet: Option<Keypair>,
+
+    /// The key acting in the instructions
+    pub authority: Pubkey,
+
+    /// The key paying for the transactions and the accounts they create
+    pub fee_payer: Pubkey,
+
+    /// The durable nonce account and its authority, replacing the recent blockhash
+    pub nonce: Option<(Pubkey, Pubkey)>,
+
+    /// Prints unsigned transactions in this encoding instead of sending them
+    pub unsigned: Option<Encoding>,
+
+    /// Simulates transactions instead of sending them
+    pub dry_run: bool,
+}
+
+impl Client {
+    fn program_address(&self, seeds: &[&[u8]]) -> (Pubkey, u8) {
+        Pubkey::find_program_address(seeds, &self.config.program_id)
+    }
+
+    fn instruction(
+        &self,
+        accounts: impl ToAccountMetas,
+        data: impl InstructionData,
+        remaining_accounts: Vec<AccountMeta>,
+    ) -> Instruction {
+        let mut metas = accounts.to_account_metas(None);
+        metas.extend(remaining_accounts);
+        Instruction {
+            program_id: self.config.program_id,
+            accounts: metas,
+            data: data.data(),
+        }
+    }
+
+    /// The delegation to pass when the authority acts for a principal
+    fn delegation(&self, principal: &Pubkey) -> Pubkey {
+        let manager = self.authority;
+        if manager == *principal {
+            return manager;
+        }
+        self.program_address(&[b"delegation", principal.as_ref(), manager.as_ref()])
+            .0
+    }
+
+    /// The current value of the durable nonce
+    fn nonce(&self, account: &Pubkey, authority: &Pubkey) -> Result<Hash> {
+        let data = self
+            .rpc
+            .get_account_data(account)?
+            .ok_or_else(|| anyhow!("Nonce account {} does not exist", account))?;
+        let versions: nonce::state::Versions = bincode::deserialize(&data)
+            .map_err(|_| anyhow!("{} is not a nonce account", account))?;
+        match versions.convert_to_current() {
+            nonce::State::Initialized(data) if data.authority == *authority => Ok(data.blockhash),
+            nonce::State::Initialized(data) => bail!(
+                "The authority of nonce account {} is {}",
+                account,
+                data.authority
+            ),
+            nonce::State::Uninitialized => bail!("Nonce account {} is not initialized", account),
+        }
+    }
+
+    /// Sends the instructions in a single transaction, or prints it unsigned
+    pub fn send(&self, instructions: Vec<Instruction>) -> Result<()> {
+        let mut transaction = match &self.nonce {
+            Some((account, authority)) => Transaction::new_with_nonce(
+                instructions,
+                &self.fee_payer,
+                account,
+                authority,
+                self.nonce(account, authority)?,
+            ),
+            None => Transaction::new(
+                &instructions,
+                &self.fee_payer,
+                self.rpc.get_latest_blockhash()?,
+            ),
+        };
+
+        if let Some(encoding) = self.unsigned {
+            println!("{}", transaction.encode(encoding));
+            return Ok(());
+        }
+        if let Some(wallet) = &self.wallet {
+            if transaction.signers().contains(&wallet.pubkey()) {
+                transaction.sign(wallet)?;
+            }
+        }
+
+        broadcast(&self.rpc, &transaction, self.dry_run)
+    }
+
+    pub fn init_state(
+        &self,
+        fee_earner: Pubkey,
+        fee_amount: u16,
+        referral_fee: u16,
+        min_fee: u64,
+        max_fee: Option<u64>,
+    ) -> Result<Vec<Instruction>> {
+        let (state, bump) = self.program_address(&[b"state"]);
+        Ok(vec![self.instruction(
+            stead_rent::accounts::InitializeState {
+                state,
+                payer: self.fee_payer,
+                rent: sysvar::rent::ID,
+                system_program: system_program::ID,
+            },
+            stead_rent::instruction::InitializeState {
+                bump,
+                fee_earner,
+                fee_amount,
+                referral_fee,
+                min_fee,
+                max_fee,
+            },
+            vec![],
+        )])
+    }
+
+    pub fn set_state(
+        &self,
+        fee_earner: Pubkey,
+        fee_amount: u16,
+        referral_fee: u16,
+        min_fee: u64,
+        max_fee: Option<u64>,
+        paused: bool,
+    ) -> Result<Vec<Instruction>> {
+        Ok(vec![self.instruction(
+            stead_rent::accounts::SetState {
+                state: self.config.state,
+                owner: self.authority,
+            },
+            stead_rent::instruction::SetState {
+                fee_earner,
+                fee_amount,
+                referral_fee,
+                min_fee,
+                max_fee,
+                paused,
+            },
+            vec![],
+        )])
+    }
+
+    pub fn set_fee_recipients(&self, recipients: Vec<Share>) -> Result<Vec<Instruction>> {
+        Ok(vec![self.instruction(
+            stead_rent::accounts::SetFeeRecipients {
+                state: self.config.state,
+                owner: self.authority,
+            },
+            stead_rent::instruction::SetFeeRecipients { recipients },
+            vec![],
+        )])
+    }
+
+    pub fn set_allowed_collections(&self, collections: Vec<Pubkey>) -> Result<Vec<Instruction>> {
+        Ok(vec![self.instruction(
+            stead_rent::accounts::SetAllowedCollections {
+                state: self.config.state,
+                owner: self.authority,
+            },
+            stead_rent::instruction::SetAllowedCollections { collections },
+            vec![],
+        )])
+    }
+
+    /// Hands the state over to admins, signed by the fee earner
+    pub fn init_multisig(
+        &self,
+        admins: Vec<Pubkey>,
+        threshold: u8,
+        timelock: i64,
+    ) -> Result<Vec<Instruction>> {
+        Ok(vec![self.instruction(
+            stead_rent::accounts::InitializeMultisig {
+                state: self.config.state,
+                owner: self.authority,
+            },
+            stead_rent::instruction::InitializeMultisig {
+                admins,
+                threshold,
+                timelock,
+            },
+            vec![],
+        )])
+    }
+
+    /// Proposes a change of the state, approved by the authority
+    pub fn propose(&self, action: ProposalAction) -> Result<Vec<Instruction>> {
+        let state: State = fetch(&self.rpc, &self.config.state)?;
+        let (proposal, bump) =
+            self.program_address(&[b"proposal", &state.n_proposals.to_le_bytes()]);
+        eprintln!("Proposal {}", proposal);
+
+        Ok(vec![self.instruction(
+            stead_rent::accounts::CreateProposal {
+                state: self.config.state,
+                proposal,
+                proposer: self.authority,
+                rent: sysvar::rent::ID,
+                system_program: system_program::ID,
+            },
+            stead_rent::instruction::CreateProposal { bump, action },
+            vec![],
+        )])
+    }
+
+    pub fn approve(&self, proposal: Pubkey) -> Result<Vec<Instruction>> {
+        Ok(vec![self.instruction(
+            stead_rent::accounts::ApproveProposal {
+                state: self.config.state,
+                proposal,
+                admin: self.authority,
+            },
+            stead_rent::instruction::ApproveProposal {},
+            vec![],
+        )])
+    }
+
+    pub fn execute(&self, proposal: Pubkey) -> Result<Vec<Instruction>> {
+        let account: Proposal = fetch(&self.rpc, &proposal)?;
+        Ok(vec![self.instruction(
+            stead_rent::accounts::ExecuteProposal {
+                state: self.config.state,
+                proposal,
+                proposer: account.proposer,
+            },
+            stead_rent::instruction::ExecuteProposal {},
+            vec![],
+        )])
+    }
+
+    pub fn cancel(&self, proposal: Pubkey) -> Result<Vec<Instruction>> {
+        let account: Proposal = fetch(&self.rpc, &proposal)?;
+        Ok(vec![self.instruction(
+            stead_rent::accounts::CancelProposal {
+                state: self.config.state,
+                proposal,
+                proposer: account.proposer,
+                authority: self.authority,
+            },
+            stead_rent::instruction::CancelProposal {},
+            vec![],
+        )])
+    }
+
+    /// Opens a room of a property held by the authority
+    pub fn create_exhibition(
+        &self,
+        mint: Pubkey,
+        exhibitor: Pubkey,
+        renter_fee: u16,
+        notice_period: i64,
+        deposit_policy: DepositPolicy,
+        curated: bool,
+    ) -> Result<Vec<Instruction>> {
+        let renter = self.authority;
+        let (stead, stead_bump) = self.program_address(&[b"stead", mint.as_ref()]);
+        let n_rooms = match self.rpc.get_account_data(&stead)? {
+            Some(_) => fetch::<Stead>(&self.rpc, &stead)?.n_rooms,
+            None => 0,
+        };
+        let (exhibition, exhibition_bump) =
+            self.program_address(&[b"exhibition", mint.as_ref(), &n_rooms.to_le_bytes()]);
+        let (escrow, escrow_bump) = self.program_address(&[b"escrow", mint.as_ref()]);
+        let (exhibition_token_account, exhibition_token_bump) =
+            self.program_address(&[b"token_account", mint.as_ref()]);
+
+        Ok(vec![self.instruction(
+            stead_rent::accounts::InitializeExhibition {
+                state: self.config.state,
+                stead,
+                exhibition,
+                escrow,
+                exhibition_token_mint: mint,
+                exhibition_token_account,
+                renter,
+                renter_account: get_associated_token_address(&renter, &mint),
+                exhibitor,
+                payer: self.fee_payer,
+                token_program: token::ID,
+                rent: sysvar::rent::ID,
+                system_program: system_program::ID,
+            },
+            stead_rent::instruction::InitializeExhibition {
+                bumps: InitExhibitionBumpSeeds {
+                    stead: stead_bump,
+                    exhibition: exhibition_bump,
+                    escrow: escrow_bump,
+                    exhibition_token: exhibition_token_bump,
+                },
+                renter_fee,
+                shares: vec![],
+                notice_period,
+                deposit_policy,
+                curated,
+            },
+            vec![],
+        )])
+    }
+
+    pub fn cancel_exhibition(&self, exhibition: Pubkey) -> Result<Vec<Instruction>> {
+        let account: Exhibition = fetch(&self.rpc, &exhibition)?;
+        Ok(vec![self.instruction(
+            stead_rent::accounts::CancelExhibition {
+                exhibition,
+                renter: account.renter,
+                authority: self.authority,
+                delegation: self.delegation(&account.renter),
+            },
+            stead_rent::instruction::CancelExhibition {},
+            vec![],
+        )])
+    }
+
+    pub fn close_exhibition(&self, exhibition: Pubkey) -> Result<Vec<Instruction>> {
+        let account: Exhibition = fetch(&self.rpc, &exhibition)?;
+        let property = account.property;
+        let (stead, _) = self.program_address(&[b"stead", property.as_ref()]);
+        let registry: Stead = fetch(&self.rpc, &stead)?;
+        Ok(vec![self.instruction(
+            stead_rent::accounts::CloseExhibition {
+                stead,
+                exhibition,
+                renter: account.renter,
+                renter_account: get_associated_token_address(&account.renter, &property),
+                escrow: self.program_address(&[b"escrow", property.as_ref()]).0,
+                exhibition_token_account: self
+                    .program_address(&[b"token_account", property.as_ref()])
+                    .0,
+                deposit_payer: registry.payer,
+                token_program: token::ID,
+            },
+            stead_rent::instruction::CloseExhibition {},
+            vec![],
+        )])
+    }
+
+    /// Deposits a token held by the authority, which becomes its seller
+    pub fn deposit(
+        &self,
+        exhibition: Pubkey,
+        mint: Pubkey,
+        price: u64,
+        currency: Currency,
+        metadata: ItemMetadata,
+    ) -> Result<Vec<Instruction>> {
+        let account: Exhibition = fetch(&self.rpc, &exhibition)?;
+        let exhibitor = self.authority;
+        let (exhibition_item, item_bump) =
+            self.program_address(&[b"item", exhibition.as_ref(), mint.as_ref()]);
+        let (deposited_token_account, token_account_bump) =
+            self.program_address(&[b"token_account", mint.as_ref()]);
+        let (provenance, provenance_bump) = self.program_address(&[b"provenance", mint.as_ref()]);
+        let n_exhibitions = match self.rpc.get_account_data(&provenance)? {
+            Some(_) => fetch::<Provenance>(&self.rpc, &provenance)?.n_exhibitions,
+            None => 0,
+        };
+        let (provenance_entry, provenance_entry_bump) =
+            self.program_address(&[b"provenance", mint.as_ref(), &n_exhibitions.to_le_bytes()]);
+
+        // Approved artists pass their approval along with the deposit
+        let mut remaining_accounts = vec![];
+        if account.deposit_policy == DepositPolicy::Approved {
+            let (approval, _) =
+                self.program_address(&[b"artist", exhibition.as_ref(), exhibitor.as_ref()]);
+            remaining_accounts.push(AccountMeta::new_readonly(approval, false));
+        }
+
+        // The metadata of the token proves its collection when deposits are restricted
+        let state: State = fetch(&self.rpc, &self.config.state)?;
+        if !state.allowed_collections.is_empty() {
+            let (metadata, _) = Pubkey::find_program_address(
+                &[b"metadata", token_metadata::ID.as_ref(), mint.as_ref()],
+                &token_metadata::ID,
+            );
+            remaining_accounts.push(AccountMeta::new_readonly(metadata, false));
+        }
+
+        Ok(vec![self.instruction(
+            stead_rent::accounts::DepositToken {
+                state: self.config.state,
+                exhibition,
+                exhibition_item,
+                escrow: self
+                    .program_address(&[b"escrow", account.property.as_ref()])
+                    .0,
+                deposited_token_mint: mint,
+                deposited_token_account,
+                provenance,
+                provenance_entry,
+                exhibitor,
+                authority: exhibitor,
+                delegation: exhibitor,
+                exhibitor_account: get_associated_token_address(&exhibitor, &mint),
+                payer: self.fee_payer,
+                token_program: token::ID,
+                rent: sysvar::rent::ID,
+                system_program: system_program::ID,
+            },
+            stead_rent::instruction::DepositToken {
+                bumps: DepositTokenSeedBumps {
+                    item: item_bump,
+                    token_account: token_account_bump,
+                    provenance: provenance_bump,
+                    provenance_entry: provenance_entry_bump,
+                },
+                price,
+                currency,
+                metadata,
+                proof: vec![],
+            },
+            remaining_accounts,
+        )])
+    }
+
+    /// Sends a token back to its seller, on behalf of the seller or one of its managers
+    pub fn withdraw(&self, exhibition: Pubkey, mint: Pubkey) -> Result<Vec<Instruction>> {
+        let account: Exhibition = fetch(&self.rpc, &exhibition)?;
+        let (exhibition_item, _) =
+            self.program_address(&[b"item", exhibition.as_ref(), mint.as_ref()]);
+        let item: ExhibitionItem = fetch(&self.rpc, &exhibition_item)?;
+        let (deposited_token_account, token_account_bump) =
+            self.program_address(&[b"token_account", mint.as_ref()]);
+
+        Ok(vec![self.instruction(
+            stead_rent::accounts::WithdrawToken {
+                state: self.config.state,
+                exhibition,
+                exhibitor: item.seller,
+                authority: self.authority,
+                delegation: self.delegation(&item.seller),
+                exhibitor_account: get_associated_token_address(&item.seller, &mint),
+                exhibition_item,
+                escrow: self
+                    .program_address(&[b"escrow", account.property.as_ref()])
+                    .0,
+                deposited_token_mint: mint,
+                deposited_token_account,
+                deposit_payer: item.payer,
+                token_program: token::ID,
+            },
+            stead_rent::instruction::WithdrawToken {
+                _bumps: WithdrawTokenSeedBumps {
+                    token_account: token_account_bump,
+                },
+            },
+            vec![],
+        )])
+    }
+
+    /// Buys a token for the authority, paying at most `max_lamports`
+    pub fn buy(
+        &self,
+        exhibition: Pubkey,
+        mint: Pubkey,
+        max_lamports: Option<u64>,
+    ) -> Result<Vec<Instruction>> {
+        let buyer = self.authority;
+        let account: Exhibition = fetch(&self.rpc, &exhibition)?;
+        let state: State = fetch(&self.rpc, &self.config.state)?;
+        let (exhibition_item, item_bump) =
+            self.program_address(&[b"item", exhibition.as_ref(), mint.as_ref()]);
+        let item: ExhibitionItem = fetch(&self.rpc, &exhibition_item)?;
+        let max_lamports = match (max_lamports, &item.currency) {
+            (Some(max_lamports), _) => max_lamports,
+            (None, Currency::Lamports) => item.price,
+            (None, Currency::UsdCents) => bail!("Items priced in USD need --max-lamports"),
+        };
+
+        let (deposited_token_account, token_account_bump) =
+            self.program_address(&[b"token_account", mint.as_ref()]);
+        let (buyer_record, buyer_record_bump) =
+            self.program_address(&[b"buyer_record", exhibition.as_ref(), buyer.as_ref()]);
+        let (provenance, _) = self.program_address(&[b"provenance", mint.as_ref()]);
+        let n_sales = fetch::<Provenance>(&self.rpc, &provenance)?.n_sales;
+        let (sale_receipt, sale_receipt_bump) =
+            self.program_address(&[b"receipt", mint.as_ref(), &n_sales.to_le_bytes()]);
+
+        let mut instructions = vec![];
+        let buyer_account = get_associated_token_address(&buyer, &mint);
+        if self.rpc.get_account_data(&buyer_account)?.is_none() {
+            instructions.push(create_associated_token_account(
+                &self.fee_payer,
+                &buyer,
+                &mint,
+            ));
+        }
+
+        let shares = account
+            .shares
+            .iter()
+            .chain(state.fee_recipients.iter())
+            .map(|share| AccountMeta::new(share.recipient, false))
+            .collect();
+        instructions.push(
+            self.instruction(
+                stead_rent::accounts::BuyToken {
+                    state: self.config.state,
+                    exhibition,
+                    exhibitor: item.seller,
+                    exhibition_item,
+                    escrow: self
+                        .program_address(&[b"escrow", account.property.as_ref()])
+                        .0,
+                    deposited_token_mint: mint,
+                    deposited_token_account,
+                    deposit_payer: item.payer,
+                    buyer,
+                    buyer_record,
+                    provenance,
+                    sale_receipt,
+                    buyer_account,
+                    renter: account.renter,
+                    dao: state.fee_earner,
+                    price_oracle: state.price_oracle,
+                    token_program: token::ID,
+                    rent: sysvar::rent::ID,
+                    system_program: system_program::ID,
+                },
+                stead_rent::instruction::BuyToken {
+                    bumps: BuyTokenSeedBumps {
+                        item: item_bump,
+                        token_account: token_account_bump,
+                        buyer_record: buyer_record_bump,
+                        sale_receipt: sale_receipt_bump,
+                    },
+                    proof: vec![],
+                    max_lamports,
+                },
+                shares,
+            ),
+        );
+
+        Ok(instructions)
+    }
+}
+
+/// Sends a fully signed transaction, or simulates it and prints its logs
+pub fn broadcast(rpc: &RpcClient, transaction: &Transaction, dry_run: bool) -> Result<()> {
+    let missing = transaction.missing_signers();
+    if !missing.is_empty() {
+        let missing: Vec<String> = missing.iter().map(Pubkey::to_string).collect();
+        bail!("Missing signatures from {}", missing.join(", "));
+    }
+
+    if dry_run {
+        for log in rpc.simulate_transaction(&transaction.serialize())? {
+            println!("{}", log);
+        }
+        println!("Simulation succeeded");
+    } else {
+        let signature = rpc.send_transaction(&transaction.serialize())?;
+        println!("{}", signature);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+
+/// Where and how the CLI talks to a deployment
+pub struct Config {
+    /// The JSON RPC endpoint of the cluster
+    pub url: String,
+
+    /// The wallet signing and paying the transactions
+    pub wallet: PathBuf,
+
+    /// The deployed stead-rent program
+    pub program_id: Pubkey,
+
+    /// The global state of the deployment
+    pub state: Pubkey,
+}
+
+impl Config {
+    /// Reads the provider from `Anchor.toml` and the program from `deployment.json`
+    /// The program falls back to the one listed in `Anchor.toml` for the cluster
+    pub fn load(
+        anchor_toml: &Path,
+        deployment: &Path,
+        url: Option<&str>,
+        wallet: Option<&str>,
+    ) -> Result<Config> {
+        let anchor: toml::Value = fs::read_to_string(anchor_toml)
+            .with_context(|| format!("Cannot read {}", anchor_toml.display()))?
+            .parse()
+            .with_context(|| format!("Invalid {}", anchor_toml.display()))?;
+        let root = anchor_toml.parent().unwrap_or_else(|| Path::new("."));
+
+        let cluster = anchor
+            .get("provider")
+            .and_then(|provider| provider.get("cluster"))
+            .and_then(|cluster| cluster.as_str())
+            .unwrap_or("localnet");
+        let url = match url {
+            Some(url) => cluster_url(url),
+            None => cluster_url(cluster),
+        };
+
+        let wallet = match wallet {
+            Some(wallet) => PathBuf::from(wallet),
+            None => anchor
+                .get("provider")
+                .and_then(|provider| provider.get("wallet"))
+                .and_then(|wallet| wallet.as_str())
+                .map(|wallet| root.join(wallet))
+                .ok_or_else(|| anyhow!("No wallet in {}", anchor_toml.display()))?,
+        };
+
+        let deployment: Option<Value> = match fs::read_to_string(deployment) {
+            Ok(file) => Some(
+                serde_json::from_str(&file)
+                    .with_context(|| format!("Invalid {}", deployment.display()))?,
+            ),
+            Err(_) => None,
+        };
+        let program_id = match deployment
+            .as_ref()
+            .and_then(|deployment| deployment["programKey"].as_str())
+        {
+            Some(key) => parse_pubkey(key)?,
+            None => anchor
+                .get("programs")
+                .and_then(|programs| programs.get(cluster))
+                .and_then(|programs| programs.get("stead_rent"))
+                .and_then(|key| key.as_str())
+                .map(parse_pubkey)
+                .transpose()?
+                .unwrap_or(stead_rent::ID),
+        };
+
+        let (derived_state, _) = Pubkey::find_program_address(&[b"state"], &program_id);
+        let state = match deployment
+            .as_ref()
+            .and_then(|deployment| deployment["stateKey"].as_str())
+        {
+            Some(key) => parse_pubkey(key)?,
+            None => derived_state,
+        };
+
+        Ok(Config {
+            url,
+            wallet,
+            program_id,
+            state,
+        })
+    }
+}
+
+/// Expands the monikers used by Anchor into RPC endpoints
+fn cluster_url(cluster: &str) -> String {
+    match cluster {
+        "localnet" | "l" => "http://127.0.0.1:8899".to_string(),
+        "devnet" | "d" => "https://api.devnet.solana.com".to_string(),
+        "testnet" | "t" => "https://api.testnet.solana.com".to_string(),
+        "mainnet" | "mainnet-beta" | "m" => "https://api.mainnet-beta.solana.com".to_string(),
+        url => url.to_string(),
+    }
+}
+
+pub fn parse_pubkey(key: &str) -> Result<Pubkey> {
+    Pubkey::from_str(key).map_err(|_| anyhow!("Invalid public key {}", key))
+}
@@ -0,0 +1,126 @@
+use std::fs;
+use std::path::Path;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, bail, Context, Result};
+use ed25519_dalek::{PublicKey, Signature, Signer};
+use zeroize::Zeroizing;
+
+/// An ed25519 keypair, stored as the usual JSON array of 64 bytes
+/// The secret is wiped from memory when the keypair is dropped
+pub struct Keypair(ed25519_dalek::Keypair);
+
+impl Keypair {
+    /// Reads a keypair file written by `solana-keygen`
+    pub fn read(path: &Path) -> Result<Keypair> {
+        let file = Zeroizing::new(
+            fs::read_to_string(path)
+                .with_context(|| format!("Cannot read keypair {}", path.display()))?,
+        );
+        let bytes: Zeroizing<Vec<u8>> = Zeroizing::new(
+            serde_json::from_str(&file)
+                .with_context(|| format!("Invalid keypair {}", path.display()))?,
+        );
+        Keypair::from_bytes(&bytes).with_context(|| format!("Invalid keypair {}", path.display()))
+    }
+
+    /// Builds a keypair from its secret followed by its public key
+    pub fn from_bytes(bytes: &[u8]) -> Result<Keypair> {
+        let keypair = ed25519_dalek::Keypair::from_bytes(bytes)
+            .map_err(|_| anyhow!("Expected 64 bytes, got {}", bytes.len()))?;
+        if PublicKey::from(&keypair.secret) != keypair.public {
+            bail!("Public key does not match the secret");
+        }
+        Ok(Keypair(keypair))
+    }
+
+    /// Derives the keypair of a secret
+    #[cfg(test)]
+    pub fn from_secret(secret: [u8; 32]) -> Keypair {
+        let secret = ed25519_dalek::SecretKey::from_bytes(&secret).unwrap();
+        let public = PublicKey::from(&secret);
+        Keypair(ed25519_dalek::Keypair { secret, public })
+    }
+
+    pub fn pubkey(&self) -> Pubkey {
+        Pubkey::new_from_array(self.0.public.to_bytes())
+    }
+
+    /// Signs a message following RFC 8032
+    pub fn sign(&self, message: &[u8]) -> [u8; 64] {
+        self.0.sign(message).to_bytes()
+    }
+}
+
+/// Checks a signature of a message following RFC 8032, rejecting malleable signatures and weak keys
+pub fn verify(pubkey: &Pubkey, message: &[u8], signature: &[u8; 64]) -> bool {
+    let public = match PublicKey::from_bytes(pubkey.as_ref()) {
+        Ok(public) => public,
+        Err(_) => return false,
+    };
+    let signature = match Signature::from_bytes(signature) {
+        Ok(signature) => signature,
+        Err(_) => return false,
+    };
+    public.verify_strict(message, &signature).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    // Test vectors 1 and 2 of RFC 8032, section 7.1
+    const VECTORS: &[(&str, &str, &str, &str)] = &[
+        (
+            "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+            "",
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+        ),
+        (
+            "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+            "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+            "72",
+            "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+        ),
+    ];
+
+    #[test]
+    fn signs_rfc_8032_vectors() {
+        for (secret, public, message, signature) in VECTORS {
+            let keypair = Keypair::from_bytes(&decode_hex(&[*secret, *public].concat())).unwrap();
+            assert_eq!(keypair.pubkey().to_bytes().to_vec(), decode_hex(public));
+
+            let message = decode_hex(message);
+            let signed = keypair.sign(&message);
+            assert_eq!(signed.to_vec(), decode_hex(signature));
+            assert!(verify(&keypair.pubkey(), &message, &signed));
+        }
+    }
+
+    #[test]
+    fn rejects_tampered_signatures() {
+        let (secret, public, _, _) = VECTORS[1];
+        let keypair = Keypair::from_bytes(&decode_hex(&[secret, public].concat())).unwrap();
+        let mut signature = keypair.sign(b"message");
+        assert!(!verify(&keypair.pubkey(), b"massage", &signature));
+
+        signature[0] ^= 1;
+        assert!(!verify(&keypair.pubkey(), b"message", &signature));
+    }
+
+    #[test]
+    fn rejects_mismatched_keys() {
+        let (secret, _, _, _) = VECTORS[0];
+        let (_, public, _, _) = VECTORS[1];
+        assert!(Keypair::from_bytes(&decode_hex(&[secret, public].concat())).is_err());
+        assert!(Keypair::from_bytes(&decode_hex(secret)).is_err());
+    }
+}
@@ -0,0 +1,570 @@
+//! Command line client to operate stead-rent deployments
+
+mod accounts;
+mod commands;
+mod config;
+mod keypair;
+mod offline;
+mod rpc;
+mod transaction;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Result};
+use stead_rent::state::*;
+
+use crate::commands::{broadcast, Client};
+use crate::config::{parse_pubkey, Config};
+use crate::keypair::Keypair;
+use crate::transaction::{Encoding, Transaction};
+
+const USAGE: &str = "Operate a stead-rent deployment
+
+USAGE:
+    stead-rent [OPTIONS] <COMMAND> [ARGS]
+
+OPTIONS:
+    --url <URL>              RPC endpoint or cluster moniker, defaults to the Anchor.toml provider
+    --keypair <PATH>         Wallet signing the transactions, defaults to the Anchor.toml provider
+    --config <PATH>          Anchor.toml of the deployment [default: Anchor.toml]
+    --deployment <PATH>      deployment.json of the deployment [default: deployment.json]
+    --dry-run                Simulate the transactions instead of sending them
+    --authority <KEY>        Key acting in the instructions, defaults to the wallet
+    --fee-payer <KEY>        Key paying for the transactions, defaults to the wallet
+    --nonce <ACCOUNT>        Durable nonce account replacing the recent blockhash
+    --nonce-authority <KEY>  Authority of the nonce account, defaults to the fee payer
+    --unsigned               Print the unsigned transactions instead of sending them
+    --encoding <ENCODING>    Encoding of printed and read transactions, base58 or base64
+                             [default: base64]
+
+COMMANDS:
+    init-state --fee-earner <KEY> --fee <BPS> --referral-fee <BPS> [--min-fee <LAMPORTS>] [--max-fee <LAMPORTS>]
+    set-state --fee-earner <KEY> --fee <BPS> --referral-fee <BPS> [--min-fee <LAMPORTS>] [--max-fee <LAMPORTS>]
+        [--paused]
+    set-fee-recipients [--recipients <KEY:BPS,...>]
+    set-allowed-collections [--collections <KEY,...>]
+    init-multisig --admins <KEY,...> --threshold <N> [--timelock <SECONDS>]
+    propose set-fees --fee <BPS> --referral-fee <BPS> [--min-fee <LAMPORTS>] [--max-fee <LAMPORTS>]
+    propose set-fee-earner --fee-earner <KEY>
+    propose set-fee-recipients [--recipients <KEY:BPS,...>]
+    propose set-price-oracle --oracle <KEY> --max-price-age <SECONDS> --max-confidence <BPS>
+    propose set-allowed-collections [--collections <KEY,...>]
+    propose pause|resume
+    propose set-admins --admins <KEY,...> --threshold <N> [--timelock <SECONDS>]
+    approve <PROPOSAL>
+    execute <PROPOSAL>
+    cancel <PROPOSAL>
+    create-exhibition <PROPERTY_MINT> [--exhibitor <KEY>] [--renter-fee <BPS>]
+        [--notice-period <SECONDS>] [--policy exhibitor|open|approved] [--curated]
+    cancel-exhibition <EXHIBITION>
+    close-exhibition <EXHIBITION>
+    deposit <EXHIBITION> <MINT> --price <AMOUNT> [--usd] [--caption <TEXT>]
+        [--position <N>] [--uri <URI>]
+    withdraw <EXHIBITION> <MINT>
+    buy <EXHIBITION> <MINT> [--max-lamports <LAMPORTS>]
+    list states|exhibitions|items|proposals [--exhibition <EXHIBITION>]
+    show <ADDRESS>
+    sign <TRANSACTION>
+    combine <TRANSACTION> <TRANSACTION|PUBKEY=SIGNATURE>...
+    send <TRANSACTION>";
+
+/// Options taking no value
+const SWITCHES: &[&str] = &["dry-run", "unsigned", "usd", "curated", "paused", "help"];
+
+/// The positional arguments and `--name value` options of the command line
+struct Args {
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Args> {
+        let mut positional = vec![];
+        let mut options = HashMap::new();
+        while let Some(arg) = args.next() {
+            match arg.strip_prefix("--") {
+                Some(name) if SWITCHES.contains(&name) => {
+                    options.insert(name.to_string(), String::new());
+                }
+                Some(name) => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow!("Missing value for --{}", name))?;
+                    options.insert(name.to_string(), value);
+                }
+                None => positional.push(arg),
+            }
+        }
+
+        Ok(Args {
+            positional,
+            options,
+        })
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.options.contains_key(name)
+    }
+
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+
+    fn parsed<T: FromStr>(&self, name: &str) -> Result<Option<T>> {
+        self.option(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid value for --{}: {}", name, value))
+            })
+            .transpose()
+    }
+
+    fn required<T: FromStr>(&self, name: &str) -> Result<T> {
+        self.parsed(name)?
+            .ok_or_else(|| anyhow!("Missing --{}", name))
+    }
+
+    fn key_option(&self, name: &str) -> Result<Option<anchor_lang::prelude::Pubkey>> {
+        self.option(name).map(parse_pubkey).transpose()
+    }
+
+    fn positional(&self, index: usize, name: &str) -> Result<&str> {
+        self.positional
+            .get(index)
+            .map(String::as_str)
+            .ok_or_else(|| anyhow!("Missing <{}>", name))
+    }
+
+    fn pubkey(&self, index: usize, name: &str) -> Result<anchor_lang::prelude::Pubkey> {
+        parse_pubkey(self.positional(index, name)?)
+    }
+
+    fn key_list(&self, name: &str) -> Result<Vec<anchor_lang::prelude::Pubkey>> {
+        let keys: String = self.required(name)?;
+        keys.split(',').map(parse_pubkey).collect()
+    }
+
+    /// Parses a list of keys that is empty when the option is missing
+    fn optional_key_list(&self, name: &str) -> Result<Vec<anchor_lang::prelude::Pubkey>> {
+        match self.option(name) {
+            Some(keys) if !keys.is_empty() => keys.split(',').map(parse_pubkey).collect(),
+            _ => Ok(vec![]),
+        }
+    }
+
+    /// Parses wallets weighted in basis points, as `KEY:BPS,...`
+    fn shares(&self, name: &str) -> Result<Vec<Share>> {
+        let shares = match self.option(name) {
+            Some(shares) if !shares.is_empty() => shares,
+            _ => return Ok(vec![]),
+        };
+        shares
+            .split(',')
+            .map(|share| {
+                let (recipient, basis_points) = share
+                    .split_once(':')
+                    .ok_or_else(|| anyhow!("Expected KEY:BPS, got {}", share))?;
+                Ok(Share {
+                    recipient: parse_pubkey(recipient)?,
+                    basis_points: basis_points
+                        .parse()
+                        .map_err(|_| anyhow!("Invalid basis points {}", basis_points))?,
+                })
+            })
+            .collect()
+    }
+
+    fn transaction(&self, index: usize) -> Result<Transaction> {
+        Transaction::decode(self.positional(index, "TRANSACTION")?, self.encoding()?)
+    }
+
+    fn encoding(&self) -> Result<Encoding> {
+        Ok(self.parsed("encoding")?.unwrap_or(Encoding::Base64))
+    }
+}
+
+fn run(args: Args) -> Result<()> {
+    let command = match args.positional.first() {
+        Some(command) if !args.flag("help") => command.as_str(),
+        _ => {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+    };
+
+    // Offline commands do not need a deployment
+    match command {
+        "sign" => return sign(&args),
+        "combine" => return combine(&args),
+        _ => {}
+    }
+
+    let config = Config::load(
+        Path::new(args.option("config").unwrap_or("Anchor.toml")),
+        Path::new(args.option("deployment").unwrap_or("deployment.json")),
+        args.option("url"),
+        args.option("keypair"),
+    )?;
+
+    // Reading commands do not need a wallet
+    match command {
+        "list" => return list(&config, &args),
+        "show" => {
+            return accounts::inspect(
+                &rpc::RpcClient::new(&config.url),
+                &args.pubkey(1, "ADDRESS")?,
+            )
+        }
+        "send" => {
+            let transaction = args.transaction(1)?;
+            transaction.verify()?;
+            offline::check_targets(&config, &transaction)?;
+            return broadcast(
+                &rpc::RpcClient::new(&config.url),
+                &transaction,
+                args.flag("dry-run"),
+            );
+        }
+        _ => {}
+    }
+
+    let unsigned = match args.flag("unsigned") {
+        true => Some(args.encoding()?),
+        false => None,
+    };
+    let authority = args.key_option("authority")?;
+    let fee_payer = args.key_option("fee-payer")?;
+    let wallet = match (unsigned, authority, fee_payer) {
+        (Some(_), Some(_), Some(_)) => None,
+        _ => Some(Keypair::read(&config.wallet)?),
+    };
+    let wallet_key = wallet.as_ref().map(Keypair::pubkey).unwrap_or_default();
+    let authority = authority.unwrap_or(wallet_key);
+    let fee_payer = fee_payer.unwrap_or(wallet_key);
+    let nonce = match args.key_option("nonce")? {
+        Some(account) => Some((
+            account,
+            args.key_option("nonce-authority")?.unwrap_or(fee_payer),
+        )),
+        None => None,
+    };
+    if unsigned.is_some() && nonce.is_none() {
+        bail!("Unsigned transactions need a durable --nonce to stay valid until they are signed");
+    }
+
+    let client = Client {
+        rpc: rpc::RpcClient::new(&config.url),
+        config,
+        wallet,
+        authority,
+        fee_payer,
+        nonce,
+        unsigned,
+        dry_run: args.flag("dry-run"),
+    };
+    let instructions = match command {
+        "init-state" => client.init_state(
+            parse_pubkey(args.required::<String>("fee-earner")?.as_str())?,
+            args.required("fee")?,
+            args.required("referral-fee")?,
+            args.parsed("min-fee")?.unwrap_or(0),
+            args.parsed("max-fee")?,
+        )?,
+        "set-state" => client.set_state(
+            parse_pubkey(args.required::<String>("fee-earner")?.as_str())?,
+            args.required("fee")?,
+            args.required("referral-fee")?,
+            args.parsed("min-fee")?.unwrap_or(0),
+            args.parsed("max-fee")?,
+            args.flag("paused"),
+        )?,
+        "set-fee-recipients" => client.set_fee_recipients(args.shares("recipients")?)?,
+        "set-allowed-collections" => {
+            client.set_allowed_collections(args.optional_key_list("collections")?)?
+        }
+        "init-multisig" => client.init_multisig(
+            args.key_list("admins")?,
+            args.required("threshold")?,
+            args.parsed("timelock")?.unwrap_or(0),
+        )?,
+        "propose" => client.propose(proposal_action(&args)?)?,
+        "approve" => client.approve(args.pubkey(1, "PROPOSAL")?)?,
+        "execute" => client.execute(args.pubkey(1, "PROPOSAL")?)?,
+        "cancel" => client.cancel(args.pubkey(1, "PROPOSAL")?)?,
+        "create-exhibition" => {
+            let deposit_policy = match args.option("policy").unwrap_or("exhibitor") {
+                "exhibitor" => DepositPolicy::Exhibitor,
+                "open" => DepositPolicy::Open,
+                "approved" => DepositPolicy::Approved,
+                policy => bail!("Unknown deposit policy {}", policy),
+            };
+            let exhibitor = match args.option("exhibitor") {
+                Some(exhibitor) => parse_pubkey(exhibitor)?,
+                None => Default::default(),
+            };
+            client.create_exhibition(
+                args.pubkey(1, "PROPERTY_MINT")?,
+                exhibitor,
+                args.parsed("renter-fee")?.unwrap_or(0),
+                args.parsed("notice-period")?.unwrap_or(0),
+                deposit_policy,
+                args.flag("curated"),
+            )?
+        }
+        "cancel-exhibition" => client.cancel_exhibition(args.pubkey(1, "EXHIBITION")?)?,
+        "close-exhibition" => client.close_exhibition(args.pubkey(1, "EXHIBITION")?)?,
+        "deposit" => {
+            let currency = if args.flag("usd") {
+                Currency::UsdCents
+            } else {
+                Currency::Lamports
+            };
+            client.deposit(
+                args.pubkey(1, "EXHIBITION")?,
+                args.pubkey(2, "MINT")?,
+                args.required("price")?,
+                currency,
+                ItemMetadata {
+                    caption: args.option("caption").unwrap_or_default().to_string(),
+                    position: args.parsed("position")?.unwrap_or(0),
+                    uri: args.option("uri").map(String::from),
+                },
+            )?
+        }
+        "withdraw" => client.withdraw(args.pubkey(1, "EXHIBITION")?, args.pubkey(2, "MINT")?)?,
+        "buy" => client.buy(
+            args.pubkey(1, "EXHIBITION")?,
+            args.pubkey(2, "MINT")?,
+            args.parsed("max-lamports")?,
+        )?,
+        command => bail!("Unknown command {}\n\n{}", command, USAGE),
+    };
+
+    client.send(instructions)
+}
+
+fn proposal_action(args: &Args) -> Result<ProposalAction> {
+    let action = match args.positional(1, "ACTION")? {
+        "set-fees" => ProposalAction::SetFees {
+            fee_amount: args.required("fee")?,
+            referral_fee: args.required("referral-fee")?,
+            min_fee: args.parsed("min-fee")?.unwrap_or(0),
+            max_fee: args.parsed("max-fee")?,
+        },
+        "set-fee-earner" => ProposalAction::SetFeeEarner {
+            fee_earner: parse_pubkey(args.required::<String>("fee-earner")?.as_str())?,
+        },
+        "set-price-oracle" => ProposalAction::SetPriceOracle {
+            price_oracle: parse_pubkey(args.required::<String>("oracle")?.as_str())?,
+            max_price_age: args.required("max-price-age")?,
+            max_confidence: args.required("max-confidence")?,
+        },
+        "set-fee-recipients" => ProposalAction::SetFeeRecipients {
+            recipients: args.shares("recipients")?,
+        },
+        "set-allowed-collections" => ProposalAction::SetAllowedCollections {
+            collections: args.optional_key_list("collections")?,
+        },
+        "pause" => ProposalAction::SetPaused { paused: true },
+        "resume" => ProposalAction::SetPaused { paused: false },
+        "set-admins" => ProposalAction::SetAdmins {
+            admins: args.key_list("admins")?,
+            threshold: args.required("threshold")?,
+            timelock: args.parsed("timelock")?.unwrap_or(0),
+        },
+        action => bail!("Unknown proposal {}", action),
+    };
+
+    Ok(action)
+}
+
+/// Prints the signature of the wallet for a transaction, as `PUBKEY=SIGNATURE`
+fn sign(args: &Args) -> Result<()> {
+    let path = match args.option("keypair") {
+        Some(path) => PathBuf::from(path),
+        None => {
+            Config::load(
+                Path::new(args.option("config").unwrap_or("Anchor.toml")),
+                Path::new(args.option("deployment").unwrap_or("deployment.json")),
+                args.option("url"),
+                None,
+            )?
+            .wallet
+        }
+    };
+    let wallet = Keypair::read(&path)?;
+
+    let mut transaction = args.transaction(1)?;
+    transaction.sign(&wallet)?;
+    let index = transaction
+        .signers()
+        .iter()
+        .position(|signer| *signer == wallet.pubkey())
+        .unwrap_or_default();
+    println!(
+        "{}={}",
+        wallet.pubkey(),
+        bs58::encode(transaction.signatures[index].0).into_string()
+    );
+
+    Ok(())
+}
+
+/// Adds partial signatures to a transaction and prints it
+fn combine(args: &Args) -> Result<()> {
+    let mut transaction = args.transaction(1)?;
+    transaction.verify()?;
+    let encoding = args.encoding()?;
+    for part in args.positional.iter().skip(2) {
+        // Base64 transactions may end with `=` too, so signatures are recognized by parsing them
+        match offline::parse_signature(part) {
+            Ok((signer, signature)) => transaction.add_signature(&signer, signature)?,
+            Err(_) => transaction.merge(&Transaction::decode(part, encoding)?)?,
+        }
+    }
+
+    let missing = transaction.missing_signers();
+    if !missing.is_empty() {
+        let missing: Vec<String> = missing.iter().map(ToString::to_string).collect();
+        eprintln!("Still missing signatures from {}", missing.join(", "));
+    }
+    println!("{}", transaction.encode(encoding));
+
+    Ok(())
+}
+
+fn list(config: &Config, args: &Args) -> Result<()> {
+    let rpc = rpc::RpcClient::new(&config.url);
+    let program_id = &config.program_id;
+    match args.positional.get(1).map(String::as_str) {
+        Some("states") => {
+            for (address, state) in accounts::list::<State>(&rpc, program_id)? {
+                accounts::print_state(&address, &state);
+            }
+        }
+        Some("exhibitions") => {
+            for (address, exhibition) in accounts::list::<Exhibition>(&rpc, program_id)? {
+                accounts::print_exhibition(&address, &exhibition);
+            }
+        }
+        Some("items") => {
+            let exhibition = args.option("exhibition").map(parse_pubkey).transpose()?;
+            for (address, item) in accounts::list::<ExhibitionItem>(&rpc, program_id)? {
+                if exhibition.map_or(true, |exhibition| item.exhibition == exhibition) {
+                    accounts::print_item(&address, &item);
+                }
+            }
+        }
+        Some("proposals") => {
+            for (address, proposal) in accounts::list::<Proposal>(&rpc, program_id)? {
+                accounts::print_proposal(&address, &proposal);
+            }
+        }
+        _ => bail!("Expected one of states, exhibitions, items or proposals"),
+    }
+
+    Ok(())
+}
+
+fn main() {
+    if let Err(error) = Args::parse(std::env::args().skip(1)).and_then(run) {
+        eprintln!("Error: {:?}", error);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Result<Args> {
+        Args::parse(line.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn parses_options_and_switches() {
+        let args = parse("deposit EXHIBITION MINT --price 10 --usd --caption Sunset").unwrap();
+        assert_eq!(args.positional, ["deposit", "EXHIBITION", "MINT"]);
+        assert!(args.flag("usd"));
+        assert!(!args.flag("dry-run"));
+        assert_eq!(args.required::<u64>("price").unwrap(), 10);
+        assert_eq!(args.option("caption"), Some("Sunset"));
+        assert_eq!(args.parsed::<u16>("position").unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_missing_and_invalid_values() {
+        assert!(parse("buy EXHIBITION MINT --max-lamports").is_err());
+
+        let args = parse("set-state --fee ten").unwrap();
+        assert!(args.required::<u16>("fee").is_err());
+        assert!(args.required::<u16>("referral-fee").is_err());
+        assert!(args.pubkey(1, "EXHIBITION").is_err());
+    }
+
+    #[test]
+    fn parses_shares() {
+        let recipient = anchor_lang::prelude::Pubkey::new_unique();
+        let args = parse(&format!(
+            "set-fee-recipients --recipients {}:10000",
+            recipient
+        ))
+        .unwrap();
+        let shares = args.shares("recipients").unwrap();
+        assert_eq!(shares.len(), 1);
+        assert_eq!(shares[0].recipient, recipient);
+        assert_eq!(shares[0].basis_points, 10000);
+
+        assert!(parse("set-fee-recipients")
+            .unwrap()
+            .shares("recipients")
+            .unwrap()
+            .is_empty());
+        let args = parse(&format!("set-fee-recipients --recipients {}", recipient)).unwrap();
+        assert!(args.shares("recipients").is_err());
+    }
+
+    #[test]
+    fn parses_proposals() {
+        let args = parse("propose set-fees --fee 150 --referral-fee 50 --max-fee 1000").unwrap();
+        match proposal_action(&args).unwrap() {
+            ProposalAction::SetFees {
+                fee_amount,
+                referral_fee,
+                min_fee,
+                max_fee,
+            } => {
+                assert_eq!(
+                    (fee_amount, referral_fee, min_fee, max_fee),
+                    (150, 50, 0, Some(1000))
+                );
+            }
+            _ => panic!("Expected a fee proposal"),
+        }
+
+        let collection = anchor_lang::prelude::Pubkey::new_unique();
+        let args = parse(&format!(
+            "propose set-allowed-collections --collections {}",
+            collection
+        ))
+        .unwrap();
+        assert!(
+            proposal_action(&args).unwrap()
+                == ProposalAction::SetAllowedCollections {
+                    collections: vec![collection]
+                }
+        );
+        let args = parse("propose set-allowed-collections").unwrap();
+        assert!(
+            proposal_action(&args).unwrap()
+                == ProposalAction::SetAllowedCollections {
+                    collections: vec![]
+                }
+        );
+        assert!(proposal_action(&parse("propose unknown").unwrap()).is_err());
+    }
+}
@@ -0,0 +1,189 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::system_program;
+use anyhow::{anyhow, bail, Result};
+use sha2::{Digest, Sha256};
+
+use crate::config::{parse_pubkey, Config};
+use crate::transaction::Transaction;
+
+/// The admin instructions, which take the state as their first account
+const ADMIN_INSTRUCTIONS: &[&str] = &[
+    "initialize_state",
+    "set_state",
+    "set_price_oracle",
+    "set_fee_recipients",
+    "set_allowed_collections",
+    "initialize_multisig",
+    "create_proposal",
+    "approve_proposal",
+    "execute_proposal",
+    "cancel_proposal",
+];
+
+/// The index of `AdvanceNonceAccount` among the system instructions
+const ADVANCE_NONCE: [u8; 4] = [4, 0, 0, 0];
+
+/// Checks that a transaction only calls the deployment and that its admin instructions
+/// target the state of the deployment
+pub fn check_targets(config: &Config, transaction: &Transaction) -> Result<()> {
+    let (state, _) = Pubkey::find_program_address(&[b"state"], &config.program_id);
+    if config.state != state {
+        bail!(
+            "{} is not the state of program {}",
+            config.state,
+            config.program_id
+        );
+    }
+
+    let message = &transaction.message;
+    let key = |index: u8| {
+        message
+            .account_keys
+            .get(index as usize)
+            .copied()
+            .ok_or_else(|| anyhow!("Invalid transaction: unknown account {}", index))
+    };
+
+    let mut calls_program = false;
+    for (index, instruction) in message.instructions.iter().enumerate() {
+        let program_id = key(instruction.program_id_index)?;
+        if program_id == config.program_id {
+            calls_program = true;
+            let is_admin = ADMIN_INSTRUCTIONS
+                .iter()
+                .any(|name| instruction.data.starts_with(&sighash(name)));
+            let target = instruction.accounts.first().copied().map(key).transpose()?;
+            if is_admin && target != Some(config.state) {
+                bail!(
+                    "Instruction {} does not target the state {}",
+                    index,
+                    config.state
+                );
+            }
+        } else if program_id == system_program::ID
+            && index == 0
+            && instruction.data.starts_with(&ADVANCE_NONCE)
+        {
+            // Durable nonces are advanced by the first instruction
+        } else if program_id != spl_associated_token_account::id() {
+            bail!(
+                "Instruction {} calls unexpected program {}",
+                index,
+                program_id
+            );
+        }
+    }
+
+    if !calls_program {
+        bail!(
+            "The transaction does not call program {}",
+            config.program_id
+        );
+    }
+
+    Ok(())
+}
+
+/// Parses a signature made offline, given as `PUBKEY=SIGNATURE`
+pub fn parse_signature(pair: &str) -> Result<(Pubkey, [u8; 64])> {
+    let (signer, signature) = pair
+        .split_once('=')
+        .ok_or_else(|| anyhow!("Expected PUBKEY=SIGNATURE, got {}", pair))?;
+    let bytes = bs58::decode(signature)
+        .into_vec()
+        .map_err(|_| anyhow!("Invalid signature {}", signature))?;
+    if bytes.len() != 64 {
+        bail!("Invalid signature {}", signature);
+    }
+
+    let mut signature = [0; 64];
+    signature.copy_from_slice(&bytes);
+    Ok((parse_pubkey(signer)?, signature))
+}
+
+/// The discriminator Anchor prepends to the data of an instruction
+fn sighash(name: &str) -> [u8; 8] {
+    let mut discriminator = [0; 8];
+    discriminator.copy_from_slice(&Sha256::digest(format!("global:{}", name).as_bytes())[..8]);
+    discriminator
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use anchor_lang::solana_program::hash::Hash;
+    use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+    use anchor_lang::solana_program::system_instruction;
+
+    use super::*;
+
+    fn config() -> Config {
+        let program_id = stead_rent::ID;
+        let (state, _) = Pubkey::find_program_address(&[b"state"], &program_id);
+        Config {
+            url: String::new(),
+            wallet: PathBuf::new(),
+            program_id,
+            state,
+        }
+    }
+
+    fn set_state(program_id: Pubkey, state: Pubkey) -> Instruction {
+        Instruction::new_with_bytes(
+            program_id,
+            &sighash("set_state"),
+            vec![AccountMeta::new(state, false)],
+        )
+    }
+
+    fn transaction(instructions: Vec<Instruction>) -> Transaction {
+        Transaction::new(&instructions, &Pubkey::new_unique(), Hash::new_unique())
+    }
+
+    #[test]
+    fn accepts_admin_instructions_of_the_deployment() {
+        let config = config();
+        let instruction = set_state(config.program_id, config.state);
+        check_targets(&config, &transaction(vec![instruction.clone()])).unwrap();
+
+        // Through a durable nonce
+        let payer = Pubkey::new_unique();
+        let nonce = Transaction::new_with_nonce(
+            vec![instruction],
+            &payer,
+            &Pubkey::new_unique(),
+            &payer,
+            Hash::new_unique(),
+        );
+        check_targets(&config, &nonce).unwrap();
+    }
+
+    #[test]
+    fn rejects_foreign_programs() {
+        let config = config();
+        let foreign = set_state(Pubkey::new_unique(), config.state);
+        assert!(check_targets(&config, &transaction(vec![foreign.clone()])).is_err());
+
+        let instruction = set_state(config.program_id, config.state);
+        assert!(check_targets(&config, &transaction(vec![instruction, foreign])).is_err());
+
+        // The system program is only called to advance a durable nonce
+        let transfer = system_instruction::transfer(&Pubkey::new_unique(), &config.state, 1);
+        let instruction = set_state(config.program_id, config.state);
+        assert!(check_targets(&config, &transaction(vec![transfer, instruction])).is_err());
+    }
+
+    #[test]
+    fn rejects_other_state_accounts() {
+        let mut config = config();
+        let other = Pubkey::new_unique();
+        let instruction = set_state(config.program_id, other);
+        assert!(check_targets(&config, &transaction(vec![instruction])).is_err());
+
+        // The configured state must belong to the program
+        config.state = other;
+        let instruction = set_state(config.program_id, other);
+        assert!(check_targets(&config, &transaction(vec![instruction])).is_err());
+    }
+}
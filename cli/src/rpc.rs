@@ -0,0 +1,183 @@
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::Hash;
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::{json, Value};
+
+/// A minimal JSON RPC client, posting requests over HTTP
+pub struct RpcClient {
+    url: String,
+}
+
+impl RpcClient {
+    pub fn new(url: &str) -> RpcClient {
+        RpcClient {
+            url: url.to_string(),
+        }
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        let mut response: Value = match ureq::post(&self.url).send_json(request) {
+            Ok(response) => response
+                .into_json()
+                .with_context(|| format!("Invalid response to {}", method))?,
+            // Failed requests still carry a JSON RPC error worth reporting
+            Err(ureq::Error::Status(status, response)) => response
+                .into_json()
+                .with_context(|| format!("{} failed with status {}", method, status))?,
+            Err(err) => bail!("Cannot reach {}: {}", self.url, err),
+        };
+        if let Some(error) = response.get("error") {
+            let logs = error["data"]["logs"]
+                .as_array()
+                .map(|logs| {
+                    logs.iter()
+                        .filter_map(|log| log.as_str())
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .unwrap_or_default();
+            bail!("{} failed: {}\n{}", method, error["message"], logs);
+        }
+
+        Ok(response["result"].take())
+    }
+
+    /// Fetches the data of an account, if it exists
+    pub fn get_account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>> {
+        let result = self.call(
+            "getAccountInfo",
+            json!([address.to_string(), { "encoding": "base64" }]),
+        )?;
+        if result["value"].is_null() {
+            return Ok(None);
+        }
+
+        decode_data(&result["value"]["data"]).map(Some)
+    }
+
+    /// Fetches every account of the program starting with a discriminator
+    pub fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        discriminator: &[u8],
+    ) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+        let result = self.call(
+            "getProgramAccounts",
+            json!([
+                program_id.to_string(),
+                {
+                    "encoding": "base64",
+                    "filters": [
+                        { "memcmp": { "offset": 0, "bytes": bs58::encode(discriminator).into_string() } }
+                    ],
+                },
+            ]),
+        )?;
+
+        result
+            .as_array()
+            .ok_or_else(|| anyhow!("Invalid response to getProgramAccounts"))?
+            .iter()
+            .map(|account| {
+                let address = account["pubkey"]
+                    .as_str()
+                    .and_then(|key| Pubkey::from_str(key).ok())
+                    .ok_or_else(|| anyhow!("Invalid response to getProgramAccounts"))?;
+                Ok((address, decode_data(&account["account"]["data"])?))
+            })
+            .collect()
+    }
+
+    pub fn get_latest_blockhash(&self) -> Result<Hash> {
+        let result = self.call("getLatestBlockhash", json!([]))?;
+        result["value"]["blockhash"]
+            .as_str()
+            .and_then(|hash| Hash::from_str(hash).ok())
+            .ok_or_else(|| anyhow!("Invalid response to getLatestBlockhash"))
+    }
+
+    /// Sends a serialized transaction and waits for its confirmation
+    pub fn send_transaction(&self, transaction: &[u8]) -> Result<String> {
+        let result = self.call(
+            "sendTransaction",
+            json!([base64::encode(transaction), { "encoding": "base64" }]),
+        )?;
+        let signature = result
+            .as_str()
+            .ok_or_else(|| anyhow!("Invalid response to sendTransaction"))?
+            .to_string();
+
+        for _ in 0..60 {
+            let statuses = self.call("getSignatureStatuses", json!([[signature]]))?;
+            let status = &statuses["value"][0];
+            if !status.is_null() {
+                if !status["err"].is_null() {
+                    bail!("Transaction {} failed: {}", signature, status["err"]);
+                }
+                if status["confirmationStatus"] != "processed" {
+                    return Ok(signature);
+                }
+            }
+            thread::sleep(Duration::from_millis(500));
+        }
+
+        bail!("Transaction {} was not confirmed", signature)
+    }
+
+    /// Runs a serialized transaction without committing it and returns its logs
+    pub fn simulate_transaction(&self, transaction: &[u8]) -> Result<Vec<String>> {
+        let result = self.call(
+            "simulateTransaction",
+            json!([base64::encode(transaction), { "encoding": "base64" }]),
+        )?;
+        let logs: Vec<String> = result["value"]["logs"]
+            .as_array()
+            .map(|logs| {
+                logs.iter()
+                    .filter_map(|log| log.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !result["value"]["err"].is_null() {
+            bail!(
+                "Simulation failed: {}\n{}",
+                result["value"]["err"],
+                logs.join("\n")
+            );
+        }
+
+        Ok(logs)
+    }
+}
+
+fn decode_data(data: &Value) -> Result<Vec<u8>> {
+    let data = data[0]
+        .as_str()
+        .ok_or_else(|| anyhow!("Invalid account data"))?;
+    base64::decode(data).context("Invalid account data")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_base64_account_data() {
+        let data = json!(["AQID", "base64"]);
+        assert_eq!(decode_data(&data).unwrap(), vec![1, 2, 3]);
+
+        assert!(decode_data(&json!(["not base64!", "base64"])).is_err());
+        assert!(decode_data(&json!(null)).is_err());
+    }
+}
@@ -0,0 +1,318 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::Hash;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::message::Message;
+use anchor_lang::solana_program::short_vec;
+use anyhow::{anyhow, bail, Context, Result};
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::ser::{SerializeTuple, Serializer};
+use serde::{Deserialize, Serialize};
+
+use crate::keypair::{self, Keypair};
+
+/// The text encodings of serialized transactions
+#[derive(Clone, Copy)]
+pub enum Encoding {
+    Base58,
+    Base64,
+}
+
+impl FromStr for Encoding {
+    type Err = anyhow::Error;
+
+    fn from_str(encoding: &str) -> Result<Encoding> {
+        match encoding {
+            "base58" => Ok(Encoding::Base58),
+            "base64" => Ok(Encoding::Base64),
+            encoding => bail!("Unknown encoding {}", encoding),
+        }
+    }
+}
+
+/// An ed25519 signature, serialized as its 64 bytes
+#[derive(Clone, Copy, PartialEq)]
+pub struct Signature(pub [u8; 64]);
+
+impl Signature {
+    /// The placeholder of a signature not made yet
+    const EMPTY: Signature = Signature([0; 64]);
+}
+
+impl Serialize for Signature {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(64)?;
+        for byte in &self.0 {
+            tuple.serialize_element(byte)?;
+        }
+        tuple.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Signature {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Signature, D::Error> {
+        struct SignatureVisitor;
+
+        impl<'de> Visitor<'de> for SignatureVisitor {
+            type Value = Signature;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("64 bytes")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Signature, A::Error> {
+                let mut signature = Signature::EMPTY;
+                for (index, byte) in signature.0.iter_mut().enumerate() {
+                    *byte = seq
+                        .next_element()?
+                        .ok_or_else(|| de::Error::invalid_length(index, &self))?;
+                }
+                Ok(signature)
+            }
+        }
+
+        deserializer.deserialize_tuple(64, SignatureVisitor)
+    }
+}
+
+/// A transaction in its wire format, signed by each of its required signers
+#[derive(Serialize, Deserialize)]
+pub struct Transaction {
+    /// The signatures of the required signers, in the order of the message keys
+    #[serde(with = "short_vec")]
+    pub signatures: Vec<Signature>,
+
+    /// The message being signed
+    pub message: Message,
+}
+
+impl Transaction {
+    /// Builds an unsigned transaction paid by the payer
+    pub fn new(instructions: &[Instruction], payer: &Pubkey, blockhash: Hash) -> Transaction {
+        let mut message = Message::new(instructions, Some(payer));
+        message.recent_blockhash = blockhash;
+        Transaction::from_message(message)
+    }
+
+    /// Builds an unsigned transaction that stays valid until the durable nonce advances
+    pub fn new_with_nonce(
+        instructions: Vec<Instruction>,
+        payer: &Pubkey,
+        nonce_account: &Pubkey,
+        nonce_authority: &Pubkey,
+        nonce: Hash,
+    ) -> Transaction {
+        let mut message =
+            Message::new_with_nonce(instructions, Some(payer), nonce_account, nonce_authority);
+        message.recent_blockhash = nonce;
+        Transaction::from_message(message)
+    }
+
+    fn from_message(message: Message) -> Transaction {
+        Transaction {
+            signatures: vec![Signature::EMPTY; message.header.num_required_signatures as usize],
+            message,
+        }
+    }
+
+    /// The keys expected to sign the transaction
+    pub fn signers(&self) -> &[Pubkey] {
+        &self.message.account_keys[..self.signatures.len()]
+    }
+
+    /// Adds the signature of a keypair required by the transaction
+    pub fn sign(&mut self, keypair: &Keypair) -> Result<()> {
+        let signature = keypair.sign(&self.message.serialize());
+        self.add_signature(&keypair.pubkey(), signature)
+    }
+
+    /// Adds a signature made elsewhere, after checking it signs this message
+    pub fn add_signature(&mut self, signer: &Pubkey, signature: [u8; 64]) -> Result<()> {
+        let index = self
+            .signers()
+            .iter()
+            .position(|key| key == signer)
+            .ok_or_else(|| anyhow!("{} does not sign this transaction", signer))?;
+        if !keypair::verify(signer, &self.message.serialize(), &signature) {
+            bail!("Invalid signature of {}", signer);
+        }
+        self.signatures[index] = Signature(signature);
+
+        Ok(())
+    }
+
+    /// Adds the signatures of another copy of the same transaction
+    pub fn merge(&mut self, other: &Transaction) -> Result<()> {
+        if other.message.serialize() != self.message.serialize() {
+            bail!("The transactions to combine have different messages");
+        }
+        let signers = self.signers().to_vec();
+        for (signer, signature) in signers.iter().zip(other.signatures.iter()) {
+            if *signature != Signature::EMPTY {
+                self.add_signature(signer, signature.0)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The signers that have not signed yet
+    pub fn missing_signers(&self) -> Vec<Pubkey> {
+        self.signers()
+            .iter()
+            .zip(self.signatures.iter())
+            .filter(|(_, signature)| **signature == Signature::EMPTY)
+            .map(|(signer, _)| *signer)
+            .collect()
+    }
+
+    /// Checks every signature present against the message
+    pub fn verify(&self) -> Result<()> {
+        let message = self.message.serialize();
+        for (signer, signature) in self.signers().iter().zip(self.signatures.iter()) {
+            if *signature != Signature::EMPTY && !keypair::verify(signer, &message, &signature.0) {
+                bail!("Invalid signature of {}", signer);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        // Only a length above u16::MAX could fail, which messages never reach
+        bincode::serialize(self).expect("Transactions are serializable")
+    }
+
+    pub fn deserialize(bytes: &[u8]) -> Result<Transaction> {
+        let transaction: Transaction =
+            bincode::deserialize(bytes).context("Invalid transaction")?;
+        let message = &transaction.message;
+        if message.header.num_required_signatures as usize != transaction.signatures.len()
+            || message.account_keys.len() < transaction.signatures.len()
+        {
+            bail!("Invalid transaction: wrong number of signatures");
+        }
+
+        Ok(transaction)
+    }
+
+    pub fn encode(&self, encoding: Encoding) -> String {
+        match encoding {
+            Encoding::Base58 => bs58::encode(self.serialize()).into_string(),
+            Encoding::Base64 => base64::encode(self.serialize()),
+        }
+    }
+
+    pub fn decode(text: &str, encoding: Encoding) -> Result<Transaction> {
+        let bytes = match encoding {
+            Encoding::Base58 => bs58::decode(text.trim())
+                .into_vec()
+                .context("Invalid base58 transaction")?,
+            Encoding::Base64 => {
+                base64::decode(text.trim()).context("Invalid base64 transaction")?
+            }
+        };
+        Transaction::deserialize(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::instruction::AccountMeta;
+
+    use super::*;
+
+    fn transaction(payer: &Keypair, authority: &Keypair) -> Transaction {
+        let instruction = Instruction::new_with_bytes(
+            stead_rent::ID,
+            &[1, 2, 3],
+            vec![
+                AccountMeta::new(Pubkey::new_unique(), false),
+                AccountMeta::new_readonly(authority.pubkey(), true),
+            ],
+        );
+        Transaction::new(&[instruction], &payer.pubkey(), Hash::new_unique())
+    }
+
+    #[test]
+    fn combines_signatures_made_apart() {
+        let payer = Keypair::from_secret([1; 32]);
+        let authority = Keypair::from_secret([2; 32]);
+        let unsigned = transaction(&payer, &authority);
+        assert_eq!(unsigned.signers(), [payer.pubkey(), authority.pubkey()]);
+        assert_eq!(
+            unsigned.missing_signers(),
+            [payer.pubkey(), authority.pubkey()]
+        );
+
+        // Each signer works on its own copy of the unsigned transaction
+        let encoded = unsigned.encode(Encoding::Base64);
+        let mut signed_by_payer = Transaction::decode(&encoded, Encoding::Base64).unwrap();
+        signed_by_payer.sign(&payer).unwrap();
+        let mut signed_by_authority = Transaction::decode(&encoded, Encoding::Base64).unwrap();
+        signed_by_authority.sign(&authority).unwrap();
+
+        let mut combined = Transaction::decode(&encoded, Encoding::Base64).unwrap();
+        combined.merge(&signed_by_payer).unwrap();
+        combined
+            .add_signature(&authority.pubkey(), signed_by_authority.signatures[1].0)
+            .unwrap();
+        assert!(combined.missing_signers().is_empty());
+
+        let decoded =
+            Transaction::decode(&combined.encode(Encoding::Base58), Encoding::Base58).unwrap();
+        decoded.verify().unwrap();
+        assert!(decoded.signatures == combined.signatures);
+        assert_eq!(decoded.message, unsigned.message);
+    }
+
+    #[test]
+    fn serializes_in_the_wire_format() {
+        let payer = Keypair::from_secret([1; 32]);
+        let authority = Keypair::from_secret([2; 32]);
+        let mut transaction = transaction(&payer, &authority);
+        transaction.sign(&payer).unwrap();
+
+        // A compact length, the signatures, then the message
+        let bytes = transaction.serialize();
+        assert_eq!(bytes[0], 2);
+        assert_eq!(bytes[1..65], transaction.signatures[0].0);
+        assert_eq!(bytes[65..129], [0; 64]);
+        assert_eq!(bytes[129..], transaction.message.serialize()[..]);
+    }
+
+    #[test]
+    fn rejects_invalid_signatures() {
+        let payer = Keypair::from_secret([1; 32]);
+        let authority = Keypair::from_secret([2; 32]);
+        let stranger = Keypair::from_secret([3; 32]);
+        let mut transaction = transaction(&payer, &authority);
+        assert!(transaction.sign(&stranger).is_err());
+
+        let signature = stranger.sign(&transaction.message.serialize());
+        assert!(transaction
+            .add_signature(&authority.pubkey(), signature)
+            .is_err());
+
+        // A signature tampered with after signing is caught once decoded
+        transaction.sign(&authority).unwrap();
+        let mut bytes = transaction.serialize();
+        bytes[70] ^= 1;
+        assert!(Transaction::deserialize(&bytes).unwrap().verify().is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_transactions() {
+        let payer = Keypair::from_secret([1; 32]);
+        let authority = Keypair::from_secret([2; 32]);
+        let bytes = transaction(&payer, &authority).serialize();
+        assert!(Transaction::deserialize(&bytes[..100]).is_err());
+
+        // The number of signatures must match the header of the message
+        let mut bytes = bytes[65..].to_vec();
+        bytes[0] = 1;
+        assert!(Transaction::deserialize(&bytes).is_err());
+    }
+}
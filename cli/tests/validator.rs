@@ -0,0 +1,150 @@
+//! Runs the CLI against a local `solana-test-validator` hosting the program
+//! built by `anchor build`. Ignored by default, run it with
+//! `cargo test -p stead-rent-cli -- --ignored`
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output};
+use std::thread;
+use std::time::Duration;
+
+const RPC_PORT: u16 = 18899;
+
+/// A validator running for the duration of a test
+struct Validator {
+    process: Child,
+    ledger: PathBuf,
+}
+
+impl Validator {
+    fn start(root: &Path, ledger: PathBuf) -> Validator {
+        let process = Command::new("solana-test-validator")
+            .args(["--reset", "--quiet", "--rpc-port", &RPC_PORT.to_string()])
+            .arg("--ledger")
+            .arg(&ledger)
+            .arg("--bpf-program")
+            .arg("TrXDop6spRAwHDsSpvY51PxHkvZXKGNYC6bygXZLNC2")
+            .arg(root.join("target/deploy/stead_rent.so"))
+            .spawn()
+            .expect("solana-test-validator must be installed");
+        // Dropping the validator stops it, even when it fails to start
+        let validator = Validator { process, ledger };
+
+        for _ in 0..60 {
+            if solana(&["cluster-version"]).status.success() {
+                return validator;
+            }
+            thread::sleep(Duration::from_millis(500));
+        }
+        panic!("The validator did not start");
+    }
+}
+
+impl Drop for Validator {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+        let _ = fs::remove_dir_all(&self.ledger);
+    }
+}
+
+fn url() -> String {
+    format!("http://127.0.0.1:{}", RPC_PORT)
+}
+
+fn solana(args: &[&str]) -> Output {
+    Command::new("solana")
+        .args(args)
+        .args(["--url", &url()])
+        .output()
+        .expect("solana must be installed")
+}
+
+fn stead_rent(root: &Path, wallet: &Path, args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_stead-rent"))
+        .current_dir(root)
+        .args(["--url", &url(), "--deployment", "missing.json"])
+        .arg("--keypair")
+        .arg(wallet)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+#[ignore]
+fn initializes_and_reads_the_state() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+    let dir = env::temp_dir().join(format!("stead-rent-cli-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let validator = Validator::start(root, dir.join("ledger"));
+
+    let wallet = dir.join("wallet.json");
+    let keygen = Command::new("solana-keygen")
+        .args(["new", "--no-bip39-passphrase", "--silent", "--outfile"])
+        .arg(&wallet)
+        .status()
+        .expect("solana-keygen must be installed");
+    assert!(keygen.success());
+    let wallet_key = Command::new("solana-keygen")
+        .arg("pubkey")
+        .arg(&wallet)
+        .output()
+        .unwrap();
+    let wallet_key = String::from_utf8(wallet_key.stdout).unwrap();
+    let wallet_key = wallet_key.trim();
+    assert!(solana(&["airdrop", "10", wallet_key]).status.success());
+
+    // Simulating does not create the state
+    stead_rent(
+        root,
+        &wallet,
+        &[
+            "--dry-run",
+            "init-state",
+            "--fee-earner",
+            wallet_key,
+            "--fee",
+            "150",
+            "--referral-fee",
+            "50",
+        ],
+    );
+    assert_eq!(stead_rent(root, &wallet, &["list", "states"]), "");
+
+    stead_rent(
+        root,
+        &wallet,
+        &[
+            "init-state",
+            "--fee-earner",
+            wallet_key,
+            "--fee",
+            "150",
+            "--referral-fee",
+            "50",
+        ],
+    );
+    let states = stead_rent(root, &wallet, &["list", "states"]);
+    assert!(states.contains(&format!("fee earner:      {}", wallet_key)));
+    assert!(states.contains("fee amount:      150 bps"));
+
+    let address = states
+        .lines()
+        .next()
+        .and_then(|line| line.strip_prefix("State "))
+        .unwrap();
+    let state = stead_rent(root, &wallet, &["show", address]);
+    assert!(state.contains("referral fee:    50 bps"));
+
+    drop(validator);
+    fs::remove_dir_all(&dir).unwrap();
+}
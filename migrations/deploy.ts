@@ -22,6 +22,7 @@ module.exports = async function (provider) {
     stateBump,
     provider.wallet.publicKey,
     100, // 1% fee
+    0, // No referral fee
     {
       accounts: {
         state: state,
@@ -4,4 +4,134 @@ use anchor_lang::error;
 pub enum ErrorCode {
     #[msg("Fee out of range")]
     FeeOutOfRangeError,
-}
\ No newline at end of file
+    #[msg("Caption is too long")]
+    CaptionTooLongError,
+    #[msg("URI is too long")]
+    UriTooLongError,
+    #[msg("Name or symbol is too long")]
+    NameTooLongError,
+    #[msg("Too many revenue shares")]
+    TooManySharesError,
+    #[msg("Share recipients do not match the exhibition")]
+    ShareRecipientMismatchError,
+    #[msg("Invalid referrer")]
+    InvalidReferrerError,
+    #[msg("Presale must end after it starts")]
+    InvalidPresaleWindowError,
+    #[msg("Sale has not started")]
+    SaleNotStartedError,
+    #[msg("Buyer is not allowed in the presale")]
+    NotAllowlistedError,
+    #[msg("Presale purchase cap reached")]
+    PurchaseCapReachedError,
+    #[msg("Exhibition does not sell tickets")]
+    TicketingDisabledError,
+    #[msg("Notice period must be between zero and one year")]
+    InvalidNoticePeriodError,
+    #[msg("Notice period has not elapsed")]
+    NoticePeriodNotElapsedError,
+    #[msg("Exhibition is not a proposal")]
+    NotProposedError,
+    #[msg("Only the renter or the exhibitor can do this")]
+    UnauthorizedError,
+    #[msg("Artist is not allowed to deposit in the exhibition")]
+    ArtistNotAllowedError,
+    #[msg("Only the seller of the item can do this")]
+    NotSellerError,
+    #[msg("Item is waiting for the approval of the renter")]
+    ItemPendingError,
+    #[msg("Item is not waiting for approval")]
+    ItemNotPendingError,
+    #[msg("Curated exhibitions do not accept lazy listings")]
+    CuratedExhibitionError,
+    #[msg("The property cannot host more rooms")]
+    RoomLimitReachedError,
+    #[msg("A property hosts at least one room")]
+    InvalidRoomLimitError,
+    #[msg("Exhibition still holds pieces")]
+    ExhibitionNotEmptyError,
+    #[msg("Exhibitor of a group show or a cancelled exhibition cannot be replaced")]
+    ExhibitorNotReplaceableError,
+    #[msg("Signer cannot act on behalf of this wallet")]
+    NotDelegatedError,
+    #[msg("Delegation has expired")]
+    DelegationExpiredError,
+    #[msg("Unknown delegation permissions")]
+    InvalidPermissionsError,
+    #[msg("Price account is not a valid price feed")]
+    InvalidOracleError,
+    #[msg("Price is too old")]
+    StalePriceError,
+    #[msg("Price confidence interval is too wide")]
+    PriceUncertainError,
+    #[msg("Price exceeds the maximum accepted by the buyer")]
+    PriceAboveMaximumError,
+    #[msg("Item is sold as part of a bundle")]
+    ItemBundledError,
+    #[msg("A bundle groups between 2 and 10 listed items of the same seller")]
+    InvalidBundleError,
+    #[msg("Only the renter of the exhibition can do this")]
+    NotRenterError,
+    #[msg("Only the exhibitor can do this")]
+    NotExhibitorError,
+    #[msg("Account is not the fee earner")]
+    NotFeeEarnerError,
+    #[msg("Account belongs to another exhibition")]
+    ExhibitionMismatchError,
+    #[msg("Account does not match the mint of the token")]
+    MintMismatchError,
+    #[msg("Account does not match the owner of the token")]
+    TokenOwnerMismatchError,
+    #[msg("Account must hold exactly one token")]
+    TokenAmountMismatchError,
+    #[msg("Exhibition is not active")]
+    ExhibitionNotActiveError,
+    #[msg("Only the wallet that delegated can do this")]
+    NotPrincipalError,
+    #[msg("Unexpected program")]
+    InvalidProgramError,
+    #[msg("Arithmetic overflow")]
+    MathOverflowError,
+    #[msg("Account did not pay for the deposit")]
+    NotDepositPayerError,
+    #[msg("The program is paused")]
+    PausedError,
+    #[msg("Admins must be distinct, at most 10, and reach a threshold of at least 1")]
+    InvalidAdminsError,
+    #[msg("The state is governed by its admins")]
+    StateGovernedError,
+    #[msg("Only an admin can do this")]
+    NotAdminError,
+    #[msg("Admin already approved the proposal")]
+    AlreadyApprovedError,
+    #[msg("Proposal lacks approvals")]
+    ThresholdNotReachedError,
+    #[msg("Proposal is still timelocked")]
+    TimelockError,
+    #[msg("Account is not the proposer")]
+    NotProposerError,
+    #[msg("Fee recipients must be distinct, at most 5, and share 10000 basis points")]
+    InvalidFeeRecipientsError,
+    #[msg("Fee recipients do not match the state")]
+    FeeRecipientMismatchError,
+    #[msg("Price does not cover the minimum fee")]
+    PriceBelowMinimumFeeError,
+    #[msg("Presale has not ended")]
+    PresaleRunningError,
+    #[msg("Account is not the buyer")]
+    NotBuyerError,
+    #[msg("Only the seller or the renter can dissolve a bundle before the exhibition is emptied")]
+    BundleLockedError,
+    #[msg("Fees and shares exceed the price")]
+    FeeExceedsPriceError,
+    #[msg("Property is rented by another wallet")]
+    SteadRenterMismatchError,
+    #[msg("Proposal has expired")]
+    ProposalExpiredError,
+    #[msg("Price age must be positive and confidence at most 10000 basis points")]
+    InvalidOracleSettingsError,
+    #[msg("Allowed collections must be distinct and at most 10")]
+    InvalidCollectionsError,
+    #[msg("Token is not part of an allowed collection")]
+    CollectionNotAllowedError,
+}
@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+
+/// Emitted when a token is bought from an exhibition
+#[event]
+pub struct TokenSold {
+    /// The exhibition the token was sold in
+    pub exhibition: Pubkey,
+
+    /// The mint of the sold token
+    pub mint: Pubkey,
+
+    /// The wallet that bought the token
+    pub buyer: Pubkey,
+
+    /// The price paid by the buyer
+    pub price: u64,
+
+    /// The amount paid to the exhibitor
+    pub amount_exhibitor: u64,
+
+    /// The amount paid to the renter
+    pub amount_renter: u64,
+
+    /// The amount paid to the fee earner
+    pub amount_fee_earner: u64,
+
+    /// The marketplace that referred the buyer, if any
+    pub referrer: Option<Pubkey>,
+
+    /// The amount paid to the referrer
+    pub amount_referrer: u64,
+}
+
+/// Emitted when a visitor buys a ticket to an exhibition
+#[event]
+pub struct TicketSold {
+    /// The exhibition the ticket grants entry to
+    pub exhibition: Pubkey,
+
+    /// The wallet allowed to enter
+    pub visitor: Pubkey,
+
+    /// The entrance fee paid by the visitor
+    pub price: u64,
+
+    /// The time after which the ticket is no longer valid
+    pub expires_at: i64,
+}
+
+/// Emitted when the renter hands an empty exhibition to another exhibitor
+#[event]
+pub struct ExhibitorReplaced {
+    /// The exhibition changing hands
+    pub exhibition: Pubkey,
+
+    /// The exhibitor leaving the exhibition
+    pub previous_exhibitor: Pubkey,
+
+    /// The exhibitor proposed to take over
+    pub exhibitor: Pubkey,
+
+    /// The fee earned by the renter on each sell
+    pub renter_fee: u16,
+}
+
+/// Emitted when a bundle is bought from an exhibition
+#[event]
+pub struct BundleSold {
+    /// The exhibition the bundle was sold in
+    pub exhibition: Pubkey,
+
+    /// The sold bundle
+    pub bundle: Pubkey,
+
+    /// The mints of the sold tokens
+    pub mints: Vec<Pubkey>,
+
+    /// The wallet that bought the bundle
+    pub buyer: Pubkey,
+
+    /// The price paid by the buyer
+    pub price: u64,
+
+    /// The amount paid to the exhibitor
+    pub amount_exhibitor: u64,
+
+    /// The amount paid to the renter
+    pub amount_renter: u64,
+
+    /// The amount paid to the fee earner
+    pub amount_fee_earner: u64,
+
+    /// The marketplace that referred the buyer, if any
+    pub referrer: Option<Pubkey>,
+
+    /// The amount paid to the referrer
+    pub amount_referrer: u64,
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct AcceptExhibition<'info> {
+    /// The proposed exhibition
+    #[account(mut, has_one = exhibitor @ ErrorCode::NotExhibitorError)]
+    pub exhibition: Account<'info, Exhibition>,
+
+    /// The exhibitor agreeing to the terms
+    pub exhibitor: Signer<'info>,
+}
+
+/// Starts the exhibition
+pub(crate) fn handler(
+    ctx: Context<AcceptExhibition>
+) -> ProgramResult {
+    let exhibition = &mut ctx.accounts.exhibition;
+    if exhibition.status != ExhibitionStatus::Proposed {
+        return Err(ErrorCode::NotProposedError.into());
+    }
+
+    exhibition.status = ExhibitionStatus::Active;
+
+    msg!("Exhibition accepted");
+
+    Ok(())
+}
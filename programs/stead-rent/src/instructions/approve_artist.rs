@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+#[instruction(bump: u8)]
+pub struct ApproveArtist<'info> {
+    /// The group show
+    #[account(has_one = renter @ ErrorCode::NotRenterError)]
+    pub exhibition: Account<'info, Exhibition>,
+
+    /// The permission given to the artist
+    #[account(
+        init,
+        payer = renter,
+        space = ArtistApproval::LEN,
+        seeds = [
+            b"artist".as_ref(),
+            exhibition.key().as_ref(),
+            artist.key().as_ref()
+        ],
+        bump = bump
+    )]
+    pub artist_approval: Account<'info, ArtistApproval>,
+
+    /// The artist allowed to deposit
+    pub artist: AccountInfo<'info>,
+
+    /// The owner of the property
+    #[account(mut)]
+    pub renter: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Allows an artist to deposit pieces in the exhibition
+pub(crate) fn handler(
+    ctx: Context<ApproveArtist>,
+    _bump: u8
+) -> ProgramResult {
+    let approval = &mut ctx.accounts.artist_approval;
+    approval.exhibition = ctx.accounts.exhibition.key();
+    approval.artist = ctx.accounts.artist.key();
+
+    msg!("Artist approved");
+
+    Ok(())
+}
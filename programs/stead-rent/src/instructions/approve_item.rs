@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct ApproveItem<'info> {
+    /// The curated exhibition
+    #[account(mut, has_one = renter @ ErrorCode::NotRenterError)]
+    pub exhibition: Account<'info, Exhibition>,
+
+    /// The item waiting for approval
+    #[account(
+        mut,
+        has_one = exhibition @ ErrorCode::ExhibitionMismatchError,
+        constraint = exhibition_item.status == ItemStatus::Pending @ ErrorCode::ItemNotPendingError
+    )]
+    pub exhibition_item: Account<'info, ExhibitionItem>,
+
+    /// The owner of the property
+    pub renter: Signer<'info>,
+}
+
+/// Puts a pending item up for sale
+pub(crate) fn handler(
+    ctx: Context<ApproveItem>
+) -> ProgramResult {
+    ctx.accounts.exhibition.n_pending = ctx.accounts.exhibition.n_pending
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathOverflowError)?;
+    ctx.accounts.exhibition_item.status = ItemStatus::Listed;
+
+    msg!("Item approved");
+
+    Ok(())
+}
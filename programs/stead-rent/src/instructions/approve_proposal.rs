@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct ApproveProposal<'info> {
+    /// The global state
+    #[account(
+        seeds = [b"state"],
+        bump = state.bump
+    )]
+    pub state: Account<'info, State>,
+
+    /// The proposal
+    #[account(
+        mut,
+        seeds = [
+            b"proposal".as_ref(),
+            &proposal.index.to_le_bytes()
+        ],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(constraint = state.is_admin(&admin.key()) @ ErrorCode::NotAdminError)]
+    pub admin: Signer<'info>
+}
+
+/// Approves a proposal, which can execute after the timelock once the threshold is reached
+pub(crate) fn handler(ctx: Context<ApproveProposal>) -> ProgramResult {
+    let state = &ctx.accounts.state;
+    ctx.accounts.proposal.approve(
+        ctx.accounts.admin.key(),
+        state,
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    msg!("Proposal approved");
+
+    Ok(())
+}
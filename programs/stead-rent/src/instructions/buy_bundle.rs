@@ -0,0 +1,232 @@
+use anchor_lang::prelude::*;
+use anchor_lang::AccountsClose;
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::system_instruction;
+use anchor_spl::token::{self, Token, TokenAccount};
+
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+use crate::sale::*;
+
+#[derive(Accounts)]
+pub struct BuyBundle<'info> {
+    /// The global state
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.bump,
+        constraint = !state.paused @ ErrorCode::PausedError
+    )]
+    pub state: Account<'info, State>,
+
+    /// The exhibition
+    #[account(mut, has_one = renter @ ErrorCode::NotRenterError)]
+    pub exhibition: Account<'info, Exhibition>,
+
+    /// The seller of the bundle
+    #[account(mut, constraint = exhibitor.key() == bundle.seller @ ErrorCode::NotSellerError)]
+    pub exhibitor: SystemAccount<'info>,
+
+    /// The bundle being bought
+    #[account(
+        mut,
+        close = exhibitor,
+        has_one = exhibition @ ErrorCode::ExhibitionMismatchError
+    )]
+    pub bundle: Account<'info, Bundle>,
+
+    /// The account owning stored NFTs
+    #[account(
+        seeds = [
+            b"escrow".as_ref(),
+            exhibition.property.as_ref()
+        ],
+        bump = exhibition.bumps.escrow
+    )]
+    pub escrow: AccountInfo<'info>,
+
+    /// The buyer
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// The wallet renting the property
+    #[account(mut)]
+    pub renter: SystemAccount<'info>,
+
+    /// The DAO taking a cut
+    #[account(mut, constraint = dao.key() == state.fee_earner @ ErrorCode::NotFeeEarnerError)]
+    pub dao: SystemAccount<'info>,
+
+    /// The program for interacting with the token.
+    #[account(address = token::ID @ ErrorCode::InvalidProgramError)]
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Buys every item of a bundle and split revenues
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, BuyBundle<'info>>
+) -> ProgramResult {
+    // Bundles are only sold once the public sale started
+    if let Some(presale) = &ctx.accounts.exhibition.presale {
+        if Clock::get()?.unix_timestamp < presale.end {
+            return Err(ErrorCode::SaleNotStartedError.into());
+        }
+    }
+
+    let n_items = ctx.accounts.bundle.items.len();
+    if ctx.remaining_accounts.len() < 6 * n_items {
+        return Err(ErrorCode::InvalidBundleError.into());
+    }
+    let (items, others) = ctx.remaining_accounts.split_at(6 * n_items);
+
+    let price = ctx.accounts.bundle.price;
+    let split = split_sale(
+        &ctx.accounts.state,
+        &ctx.accounts.exhibition,
+        &ctx.accounts.buyer.to_account_info(),
+        Payees {
+            exhibitor: &ctx.accounts.exhibitor,
+            renter: &ctx.accounts.renter,
+            dao: &ctx.accounts.dao,
+            others,
+        },
+        price,
+    )?;
+
+    // Each item comes with the escrow account holding its token, the buyer's account,
+    // the wallet that paid for the deposit, the provenance of the token and its receipt
+    let timestamp = Clock::get()?.unix_timestamp;
+    let mut mints = Vec::with_capacity(n_items);
+    for (key, accounts) in ctx.accounts.bundle.items.iter().zip(items.chunks(6)) {
+        let item: Account<ExhibitionItem> = Account::try_from(&accounts[0])
+            .map_err(|_| ErrorCode::InvalidBundleError)?;
+        let deposited_token_account: Account<TokenAccount> = Account::try_from(&accounts[1])
+            .map_err(|_| ErrorCode::InvalidBundleError)?;
+        let buyer_account: Account<TokenAccount> = Account::try_from(&accounts[2])
+            .map_err(|_| ErrorCode::InvalidBundleError)?;
+        let deposit_payer = &accounts[3];
+        let mut provenance: Account<Provenance> = Account::try_from(&accounts[4])
+            .map_err(|_| ErrorCode::InvalidBundleError)?;
+        let sale_receipt = &accounts[5];
+        if item.key() != *key
+            || item.bundle != Some(ctx.accounts.bundle.key())
+            || deposited_token_account.mint != item.mint
+            || deposited_token_account.owner != ctx.accounts.escrow.key()
+            || buyer_account.mint != item.mint
+            || buyer_account.owner != ctx.accounts.buyer.key()
+            || deposit_payer.key() != item.payer
+            || !deposit_payer.is_writable
+            || provenance.mint != item.mint
+            || !provenance.to_account_info().is_writable
+        {
+            return Err(ErrorCode::InvalidBundleError.into());
+        }
+
+        // Every piece gets a receipt of the bundle purchase, numbered like single sales
+        let n_sales = provenance.n_sales.to_le_bytes();
+        let (address, receipt_bump) = Pubkey::find_program_address(
+            &[b"receipt".as_ref(), item.mint.as_ref(), &n_sales],
+            ctx.program_id,
+        );
+        if address != sale_receipt.key() {
+            return Err(ErrorCode::InvalidBundleError.into());
+        }
+        create_receipt(
+            &ctx.accounts.buyer.to_account_info(),
+            sale_receipt,
+            &ctx.accounts.system_program.to_account_info(),
+            &[b"receipt".as_ref(), item.mint.as_ref(), &n_sales, &[receipt_bump]],
+            ctx.program_id,
+        )?;
+
+        let receipt = SaleReceipt {
+            mint: item.mint,
+            exhibition: ctx.accounts.exhibition.key(),
+            property: ctx.accounts.exhibition.property,
+            buyer: ctx.accounts.buyer.key(),
+            exhibitor: ctx.accounts.exhibitor.key(),
+            price,
+            split: split.clone(),
+            timestamp,
+            bundle: Some(ctx.accounts.bundle.key()),
+        };
+        receipt.try_serialize(&mut &mut sale_receipt.try_borrow_mut_data()?[..])?;
+
+        provenance.n_sales = provenance
+            .n_sales
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflowError)?;
+        provenance.exit(ctx.program_id)?;
+
+        release_token(
+            &ctx.accounts.exhibition,
+            &ctx.accounts.escrow,
+            &deposited_token_account.to_account_info(),
+            &buyer_account.to_account_info(),
+            deposit_payer,
+            &ctx.accounts.token_program.to_account_info(),
+        )?;
+
+        item.close(ctx.accounts.exhibitor.to_account_info())?;
+        mints.push(item.mint);
+    }
+
+    emit!(BundleSold {
+        exhibition: ctx.accounts.exhibition.key(),
+        bundle: ctx.accounts.bundle.key(),
+        mints,
+        buyer: ctx.accounts.buyer.key(),
+        price,
+        amount_exhibitor: split.amount_exhibitor,
+        amount_renter: split.amount_renter,
+        amount_fee_earner: split.amount_fee_earner,
+        referrer: split.referrer,
+        amount_referrer: split.amount_referrer,
+    });
+
+    let exhibition = &mut ctx.accounts.exhibition;
+    exhibition.n_pieces = exhibition
+        .n_pieces
+        .checked_sub(n_items as u64)
+        .ok_or(ErrorCode::MathOverflowError)?;
+    exhibition.stats.record_bundle_sale(n_items as u64, price, &split)?;
+    ctx.accounts.state.stats.record_bundle_sale(n_items as u64, price, &split)?;
+
+    msg!("Bundle bought");
+
+    Ok(())
+}
+
+/// Creates a receipt at its program address, paid by the buyer
+/// Anyone can send lamports to the address beforehand, so only the missing rent is transferred
+fn create_receipt<'info>(
+    buyer: &AccountInfo<'info>,
+    sale_receipt: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    program_id: &Pubkey,
+) -> ProgramResult {
+    let lamports = Rent::get()?
+        .minimum_balance(SaleReceipt::LEN)
+        .saturating_sub(sale_receipt.lamports());
+    if lamports > 0 {
+        invoke(
+            &system_instruction::transfer(buyer.key, sale_receipt.key, lamports),
+            &[buyer.clone(), sale_receipt.clone(), system_program.clone()],
+        )?;
+    }
+
+    invoke_signed(
+        &system_instruction::allocate(sale_receipt.key, SaleReceipt::LEN as u64),
+        &[sale_receipt.clone(), system_program.clone()],
+        &[seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(sale_receipt.key, program_id),
+        &[sale_receipt.clone(), system_program.clone()],
+        &[seeds],
+    )
+}
@@ -0,0 +1,355 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, MintTo, SetAuthority, Token, TokenAccount};
+use spl_token::instruction::AuthorityType;
+
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+use crate::sale::*;
+
+/// The Metaplex token metadata program
+pub mod token_metadata {
+    anchor_lang::declare_id!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BuyLazyTokenSeedBumps {
+    mint: u8,
+    buyer_record: u8,
+    provenance: u8,
+    provenance_entry: u8,
+    sale_receipt: u8,
+}
+
+#[derive(Accounts)]
+#[instruction(bumps: BuyLazyTokenSeedBumps)]
+pub struct BuyLazyToken<'info> {
+    /// The global state
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.bump,
+        constraint = !state.paused @ ErrorCode::PausedError
+    )]
+    pub state: Account<'info, State>,
+
+    /// The exhibition
+    #[account(mut, has_one = renter @ ErrorCode::NotRenterError)]
+    pub exhibition: Account<'info, Exhibition>,
+
+    /// The artist of the piece being bought
+    #[account(mut, constraint = exhibitor.key() == lazy_listing.seller @ ErrorCode::NotSellerError)]
+    pub exhibitor: SystemAccount<'info>,
+
+    /// The listing of the piece being bought
+    #[account(
+        mut,
+        close = exhibitor,
+        has_one = exhibition @ ErrorCode::ExhibitionMismatchError
+    )]
+    pub lazy_listing: Account<'info, LazyListing>,
+
+    /// The account minting the piece
+    #[account(
+        seeds = [
+            b"escrow".as_ref(),
+            exhibition.property.as_ref()
+        ],
+        bump = exhibition.bumps.escrow
+    )]
+    pub escrow: AccountInfo<'info>,
+
+    /// The mint of the piece, created with the purchase
+    #[account(
+        init,
+        payer = buyer,
+        seeds = [
+            b"lazy_mint".as_ref(),
+            lazy_listing.key().as_ref()
+        ],
+        bump = bumps.mint,
+        mint::decimals = 0,
+        mint::authority = escrow
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// The history of the piece
+    #[account(
+        init,
+        payer = buyer,
+        space = Provenance::LEN,
+        seeds = [
+            b"provenance".as_ref(),
+            mint.key().as_ref()
+        ],
+        bump = bumps.provenance
+    )]
+    pub provenance: Box<Account<'info, Provenance>>,
+
+    /// The record of the piece being shown in this exhibition
+    #[account(
+        init,
+        payer = buyer,
+        space = ProvenanceEntry::LEN,
+        seeds = [
+            b"provenance".as_ref(),
+            mint.key().as_ref(),
+            &provenance.n_exhibitions.to_le_bytes()
+        ],
+        bump = bumps.provenance_entry
+    )]
+    pub provenance_entry: Box<Account<'info, ProvenanceEntry>>,
+
+    /// The proof of the purchase
+    #[account(
+        init,
+        payer = buyer,
+        space = SaleReceipt::LEN,
+        seeds = [
+            b"receipt".as_ref(),
+            mint.key().as_ref(),
+            &provenance.n_sales.to_le_bytes()
+        ],
+        bump = bumps.sale_receipt
+    )]
+    pub sale_receipt: Box<Account<'info, SaleReceipt>>,
+
+    /// The Metaplex metadata of the piece
+    #[account(mut)]
+    pub metadata: AccountInfo<'info>,
+
+    /// The buyer
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// The purchases made by the buyer in the exhibition
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = BuyerRecord::LEN,
+        seeds = [
+            b"buyer_record".as_ref(),
+            exhibition.key().as_ref(),
+            buyer.key().as_ref()
+        ],
+        bump = bumps.buyer_record
+    )]
+    pub buyer_record: Account<'info, BuyerRecord>,
+
+    /// The buyer's account that will hold the minted piece
+    #[account(
+        init,
+        payer = buyer,
+        associated_token::mint = mint,
+        associated_token::authority = buyer
+    )]
+    pub buyer_account: Account<'info, TokenAccount>,
+
+    /// The wallet renting the property
+    #[account(mut)]
+    pub renter: SystemAccount<'info>,
+
+    /// The DAO taking a cut
+    #[account(mut, constraint = dao.key() == state.fee_earner @ ErrorCode::NotFeeEarnerError)]
+    pub dao: SystemAccount<'info>,
+
+    /// The program for interacting with the token.
+    #[account(address = token::ID @ ErrorCode::InvalidProgramError)]
+    pub token_program: Program<'info, Token>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// The program creating the metadata of the piece
+    #[account(address = token_metadata::ID @ ErrorCode::InvalidProgramError)]
+    pub token_metadata_program: AccountInfo<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize)]
+struct Creator {
+    address: Pubkey,
+    verified: bool,
+    share: u8,
+}
+
+#[derive(AnchorSerialize)]
+struct CreateMetadataAccountArgs {
+    name: String,
+    symbol: String,
+    uri: String,
+    seller_fee_basis_points: u16,
+    creators: Option<Vec<Creator>>,
+    is_mutable: bool,
+}
+
+impl<'info> BuyLazyToken<'info> {
+    fn mint_to_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.mint.to_account_info(),
+                to: self.buyer_account.to_account_info(),
+                authority: self.escrow.clone(),
+            },
+        )
+    }
+
+    fn set_authority_context(&self) -> CpiContext<'_, '_, '_, 'info, SetAuthority<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            SetAuthority {
+                current_authority: self.escrow.clone(),
+                account_or_mint: self.mint.to_account_info(),
+            },
+        )
+    }
+
+    /// Creates the Metaplex metadata of the piece, crediting the exhibitor as its creator
+    fn create_metadata(&self, signer: &[&[&[u8]]]) -> ProgramResult {
+        // Instruction 0 of the token metadata program is `CreateMetadataAccount`
+        let mut data = vec![0];
+        CreateMetadataAccountArgs {
+            name: self.lazy_listing.name.clone(),
+            symbol: self.lazy_listing.symbol.clone(),
+            uri: self.lazy_listing.uri.clone(),
+            seller_fee_basis_points: 0,
+            creators: Some(vec![Creator {
+                address: self.exhibitor.key(),
+                verified: false,
+                share: 100,
+            }]),
+            is_mutable: false,
+        }
+        .serialize(&mut data)?;
+
+        let ix = Instruction {
+            program_id: token_metadata::ID,
+            accounts: vec![
+                AccountMeta::new(self.metadata.key(), false),
+                AccountMeta::new_readonly(self.mint.key(), false),
+                AccountMeta::new_readonly(self.escrow.key(), true),
+                AccountMeta::new(self.buyer.key(), true),
+                AccountMeta::new_readonly(self.exhibitor.key(), false),
+                AccountMeta::new_readonly(self.system_program.key(), false),
+                AccountMeta::new_readonly(self.rent.key(), false),
+            ],
+            data,
+        };
+        anchor_lang::solana_program::program::invoke_signed(
+            &ix,
+            &[
+                self.metadata.clone(),
+                self.mint.to_account_info(),
+                self.escrow.clone(),
+                self.buyer.to_account_info(),
+                self.exhibitor.to_account_info(),
+                self.system_program.to_account_info(),
+                self.rent.to_account_info(),
+                self.token_metadata_program.clone(),
+            ],
+            signer,
+        )
+    }
+}
+
+/// Mints a listed piece to the buyer and split revenues
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, BuyLazyToken<'info>>,
+    bumps: BuyLazyTokenSeedBumps,
+    create_metadata: bool,
+    proof: Vec<[u8; 32]>
+) -> ProgramResult {
+    let record = &mut ctx.accounts.buyer_record;
+    record.exhibition = ctx.accounts.exhibition.key();
+    record.buyer = ctx.accounts.buyer.key();
+    check_presale(&ctx.accounts.exhibition, record, &proof)?;
+
+    let price = ctx.accounts.lazy_listing.price;
+    let split = split_sale(
+        &ctx.accounts.state,
+        &ctx.accounts.exhibition,
+        &ctx.accounts.buyer.to_account_info(),
+        Payees {
+            exhibitor: &ctx.accounts.exhibitor,
+            renter: &ctx.accounts.renter,
+            dao: &ctx.accounts.dao,
+            others: ctx.remaining_accounts,
+        },
+        price,
+    )?;
+
+    let exhibition = &ctx.accounts.exhibition;
+    let seeds = &[
+        b"escrow".as_ref(),
+        exhibition.property.as_ref(),
+        &[exhibition.bumps.escrow],
+    ];
+    let signer = &[&seeds[..]];
+
+    token::mint_to(ctx.accounts.mint_to_context().with_signer(signer), 1)?;
+
+    if create_metadata {
+        ctx.accounts.create_metadata(signer)?;
+    }
+
+    // Nobody can mint the piece again
+    token::set_authority(
+        ctx.accounts.set_authority_context().with_signer(signer),
+        AuthorityType::MintTokens,
+        None,
+    )?;
+
+    emit!(TokenSold {
+        exhibition: ctx.accounts.exhibition.key(),
+        mint: ctx.accounts.mint.key(),
+        buyer: ctx.accounts.buyer.key(),
+        price,
+        amount_exhibitor: split.amount_exhibitor,
+        amount_renter: split.amount_renter,
+        amount_fee_earner: split.amount_fee_earner,
+        referrer: split.referrer,
+        amount_referrer: split.amount_referrer,
+    });
+
+    ctx.accounts.exhibition.stats.record_sale(price, &split)?;
+    ctx.accounts.state.stats.record_sale(price, &split)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let exhibition = &ctx.accounts.exhibition;
+
+    let entry = &mut ctx.accounts.provenance_entry;
+    entry.mint = ctx.accounts.mint.key();
+    entry.exhibition = exhibition.key();
+    entry.property = exhibition.property;
+    entry.renter = exhibition.renter;
+    entry.exhibitor = ctx.accounts.exhibitor.key();
+    entry.timestamp = now;
+
+    let receipt = &mut ctx.accounts.sale_receipt;
+    receipt.mint = ctx.accounts.mint.key();
+    receipt.exhibition = exhibition.key();
+    receipt.property = exhibition.property;
+    receipt.buyer = ctx.accounts.buyer.key();
+    receipt.exhibitor = ctx.accounts.exhibitor.key();
+    receipt.price = price;
+    receipt.split = split;
+    receipt.timestamp = now;
+
+    let provenance = &mut ctx.accounts.provenance;
+    provenance.mint = ctx.accounts.mint.key();
+    provenance.bump = bumps.provenance;
+    provenance.n_exhibitions = 1;
+    provenance.n_sales = 1;
+
+    ctx.accounts.exhibition.n_pieces = ctx.accounts.exhibition.n_pieces
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathOverflowError)?;
+
+    msg!("Token minted");
+
+    Ok(())
+}
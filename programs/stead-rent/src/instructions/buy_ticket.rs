@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+use crate::sale::*;
+
+#[derive(Accounts)]
+#[instruction(bump: u8)]
+pub struct BuyTicket<'info> {
+    /// The global state
+    #[account(
+        seeds = [b"state"],
+        bump = state.bump,
+        constraint = !state.paused @ ErrorCode::PausedError
+    )]
+    pub state: Account<'info, State>,
+
+    /// The exhibition
+    #[account(
+        has_one = renter @ ErrorCode::NotRenterError,
+        constraint = exhibition.status == ExhibitionStatus::Active @ ErrorCode::ExhibitionNotActiveError
+    )]
+    pub exhibition: Account<'info, Exhibition>,
+
+    /// The ticket of the visitor
+    #[account(
+        init_if_needed,
+        payer = visitor,
+        space = Ticket::LEN,
+        seeds = [
+            b"ticket".as_ref(),
+            exhibition.key().as_ref(),
+            visitor.key().as_ref()
+        ],
+        bump = bump
+    )]
+    pub ticket: Account<'info, Ticket>,
+
+    /// The wallet entering the exhibition
+    #[account(mut)]
+    pub visitor: Signer<'info>,
+
+    /// The wallet renting the property
+    #[account(mut)]
+    pub renter: SystemAccount<'info>,
+
+    /// The artist of the exhibition, unused for group shows
+    #[account(mut, constraint = exhibitor.key() == exhibition.exhibitor || exhibition.is_group_show() @ ErrorCode::NotExhibitorError)]
+    pub exhibitor: SystemAccount<'info>,
+
+    /// The DAO taking a cut
+    #[account(mut, constraint = dao.key() == state.fee_earner @ ErrorCode::NotFeeEarnerError)]
+    pub dao: SystemAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Buys or extends a ticket and split the entrance fee
+/// The fee recipients of the state are passed as remaining accounts
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, BuyTicket<'info>>,
+    _bump: u8
+) -> ProgramResult {
+    let ticketing = match &ctx.accounts.exhibition.ticketing {
+        Some(ticketing) => ticketing,
+        None => return Err(ErrorCode::TicketingDisabledError.into()),
+    };
+
+    let price = ticketing.price;
+    let amount_exhibitor = cut(price, ticketing.exhibitor_fee);
+    let amount_fee_earner = ctx.accounts.state.fee(price);
+    let amount_renter = price
+        .checked_sub(amount_fee_earner)
+        .and_then(|amount| amount.checked_sub(amount_exhibitor))
+        .ok_or_else(|| ctx.accounts.state.shortfall_error(price))?;
+
+    let visitor = ctx.accounts.visitor.to_account_info();
+    pay(&visitor, &ctx.accounts.renter, amount_renter)?;
+    pay(&visitor, &ctx.accounts.exhibitor, amount_exhibitor)?;
+    pay_fees(
+        &ctx.accounts.state,
+        &visitor,
+        &ctx.accounts.dao,
+        ctx.remaining_accounts,
+        amount_fee_earner,
+    )?;
+
+    // Time left on a valid ticket is kept
+    let ticket = &mut ctx.accounts.ticket;
+    if ticketing.validity != 0 {
+        let now = Clock::get()?.unix_timestamp;
+        ticket.expires_at = ticket
+            .expires_at
+            .max(now)
+            .checked_add(ticketing.validity)
+            .ok_or(ErrorCode::MathOverflowError)?;
+    } else {
+        ticket.expires_at = 0;
+    }
+    ticket.exhibition = ctx.accounts.exhibition.key();
+    ticket.visitor = ctx.accounts.visitor.key();
+
+    emit!(TicketSold {
+        exhibition: ticket.exhibition,
+        visitor: ticket.visitor,
+        price,
+        expires_at: ticket.expires_at,
+    });
+
+    msg!("Ticket bought");
+
+    Ok(())
+}
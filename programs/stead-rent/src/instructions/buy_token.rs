@@ -1,12 +1,18 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
 
 use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+use crate::sale::*;
+use crate::oracle::*;
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct BuyTokenSeedBumps {
-    item: u8,
-    token_account: u8
+    pub item: u8,
+    pub token_account: u8,
+    pub buyer_record: u8,
+    pub sale_receipt: u8,
 }
 
 #[derive(Accounts)]
@@ -14,26 +20,29 @@ pub struct BuyTokenSeedBumps {
 pub struct BuyToken<'info> {
     /// The global state
     #[account(
+        mut,
         seeds = [b"state"],
-        bump = state.bump
+        bump = state.bump,
+        constraint = !state.paused @ ErrorCode::PausedError
     )]
     pub state: Account<'info, State>,
 
     /// The exhibition
-    #[account(mut)]
+    #[account(mut, has_one = renter @ ErrorCode::NotRenterError)]
     pub exhibition: Account<'info, Exhibition>,
 
-    /// The owner of the token being bought
-    #[account(mut)]
-    pub exhibitor: AccountInfo<'info>,
+    /// The seller of the token being bought
+    #[account(mut, constraint = exhibitor.key() == exhibition_item.seller @ ErrorCode::NotSellerError)]
+    pub exhibitor: SystemAccount<'info>,
 
     /// The item for sale in the exhibition
     #[account(
         mut,
         close = exhibitor,
-        constraint = 
-            exhibition_item.exhibition == exhibition.key() &&
-            exhibition_item.mint == deposited_token_mint.key()
+        has_one = exhibition @ ErrorCode::ExhibitionMismatchError,
+        constraint = exhibition_item.mint == deposited_token_mint.key() @ ErrorCode::MintMismatchError,
+        constraint = exhibition_item.status == ItemStatus::Listed @ ErrorCode::ItemPendingError,
+        constraint = exhibition_item.bundle.is_none() @ ErrorCode::ItemBundledError
     )]
     pub exhibition_item: Account<'info, ExhibitionItem>,
     
@@ -49,7 +58,7 @@ pub struct BuyToken<'info> {
 
     /// The mint of the token being bought
     #[account(mut)]
-    pub deposited_token_mint: AccountInfo<'info>,
+    pub deposited_token_mint: Account<'info, Mint>,
 
     /// The account that holds the token being bought
     #[account(
@@ -62,96 +71,157 @@ pub struct BuyToken<'info> {
     )]
     pub deposited_token_account: Account<'info, TokenAccount>,
 
+    /// The wallet that paid for the deposit, refunded when the token account is closed
+    #[account(mut, constraint = deposit_payer.key() == exhibition_item.payer @ ErrorCode::NotDepositPayerError)]
+    pub deposit_payer: SystemAccount<'info>,
+
     /// The buyer
     #[account(mut)]
     pub buyer: Signer<'info>,
 
+    /// The purchases made by the buyer in the exhibition
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = BuyerRecord::LEN,
+        seeds = [
+            b"buyer_record".as_ref(),
+            exhibition.key().as_ref(),
+            buyer.key().as_ref()
+        ],
+        bump = bumps.buyer_record
+    )]
+    pub buyer_record: Account<'info, BuyerRecord>,
+
+    /// The history of the token being bought
+    #[account(
+        mut,
+        seeds = [
+            b"provenance".as_ref(),
+            deposited_token_mint.key().as_ref()
+        ],
+        bump = provenance.bump
+    )]
+    pub provenance: Account<'info, Provenance>,
+
+    /// The proof of the purchase
+    #[account(
+        init,
+        payer = buyer,
+        space = SaleReceipt::LEN,
+        seeds = [
+            b"receipt".as_ref(),
+            deposited_token_mint.key().as_ref(),
+            &provenance.n_sales.to_le_bytes()
+        ],
+        bump = bumps.sale_receipt
+    )]
+    pub sale_receipt: Account<'info, SaleReceipt>,
+
     /// The buyer's account that will hold the token being bought
     #[account(
         mut,
-        constraint =
-            buyer_account.mint == deposited_token_mint.key() &&
-            buyer_account.owner == buyer.key()
+        constraint = buyer_account.mint == deposited_token_mint.key() @ ErrorCode::MintMismatchError,
+        constraint = buyer_account.owner == buyer.key() @ ErrorCode::TokenOwnerMismatchError
     )]
     pub buyer_account: Account<'info, TokenAccount>,
 
     /// The wallet renting the property
-    #[account(mut, constraint = renter.key() == exhibition.renter)]
-    pub renter: AccountInfo<'info>,
+    #[account(mut)]
+    pub renter: SystemAccount<'info>,
 
     /// The DAO taking a cut
-    #[account(mut, constraint = dao.key() == state.fee_earner)]
-    pub dao: AccountInfo<'info>,
+    #[account(mut, constraint = dao.key() == state.fee_earner @ ErrorCode::NotFeeEarnerError)]
+    pub dao: SystemAccount<'info>,
+
+    /// The price feed whitelisted in the state, only read for items priced in USD
+    #[account(constraint = price_oracle.key() == state.price_oracle @ ErrorCode::InvalidOracleError)]
+    pub price_oracle: AccountInfo<'info>,
 
     /// The program for interacting with the token.
-    #[account(address = token::ID)]
+    #[account(address = token::ID @ ErrorCode::InvalidProgramError)]
     pub token_program: Program<'info, Token>,
 
+    pub rent: Sysvar<'info, Rent>,
     pub system_program: Program<'info, System>,
 }
 
 /// Buys a token from the exhibition and split revenues
-pub fn handler(
-    ctx: Context<BuyToken>,
-    _bump: BuyTokenSeedBumps
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, BuyToken<'info>>,
+    _bump: BuyTokenSeedBumps,
+    proof: Vec<[u8; 32]>,
+    max_lamports: u64
 ) -> ProgramResult {
-    let exhibition = &mut ctx.accounts.exhibition;
-    exhibition.n_pieces -= 1;
-
-    let price = ctx.accounts.exhibition_item.price;
-    let amount_renter = price * (exhibition.renter_fee as u64) / 10000;
-    let amount_fee_earner = price * (ctx.accounts.state.fee_amount as u64) / 10000;
-    let amount_exhibitor = price - amount_fee_earner - amount_renter;
-
-    // Transfer to the exhibitor
-    let ix = anchor_lang::solana_program::system_instruction::transfer(
-        &ctx.accounts.buyer.key,
-        &ctx.accounts.exhibitor.key,
-        amount_exhibitor,
-    );
-    anchor_lang::solana_program::program::invoke(
-        &ix,
-        &[ctx.accounts.buyer.to_account_info(), ctx.accounts.exhibitor.clone()],
+    let record = &mut ctx.accounts.buyer_record;
+    record.exhibition = ctx.accounts.exhibition.key();
+    record.buyer = ctx.accounts.buyer.key();
+    check_presale(&ctx.accounts.exhibition, record, &proof)?;
+
+    let price = price_in_lamports(
+        &ctx.accounts.state,
+        &ctx.accounts.exhibition_item,
+        &ctx.accounts.price_oracle,
     )?;
-    // Transfer to the renter
-    let ix = anchor_lang::solana_program::system_instruction::transfer(
-        &ctx.accounts.buyer.key,
-        &ctx.accounts.renter.key,
-        amount_renter,
-    );
-    anchor_lang::solana_program::program::invoke(
-        &ix,
-        &[ctx.accounts.buyer.to_account_info(), ctx.accounts.renter.clone()],
-    )?;
-    // Transfer to the fee earner
-    let ix = anchor_lang::solana_program::system_instruction::transfer(
-        &ctx.accounts.buyer.key,
-        &ctx.accounts.dao.key,
-        amount_fee_earner,
-    );
-    anchor_lang::solana_program::program::invoke(
-        &ix,
-        &[ctx.accounts.buyer.to_account_info(), ctx.accounts.dao.clone()],
+    if price > max_lamports {
+        return Err(ErrorCode::PriceAboveMaximumError.into());
+    }
+
+    let split = split_sale(
+        &ctx.accounts.state,
+        &ctx.accounts.exhibition,
+        &ctx.accounts.buyer.to_account_info(),
+        Payees {
+            exhibitor: &ctx.accounts.exhibitor,
+            renter: &ctx.accounts.renter,
+            dao: &ctx.accounts.dao,
+            others: ctx.remaining_accounts,
+        },
+        price,
     )?;
 
-    let seeds = &[
-        b"escrow".as_ref(),
-        exhibition.property.as_ref(),
-        &[exhibition.bumps.escrow],
-    ];
-    let signer = &[&seeds[..]];
-
-    let context = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        Transfer {
-            from: ctx.accounts.deposited_token_account.to_account_info(),
-            to: ctx.accounts.buyer_account.to_account_info(),
-            authority: ctx.accounts.escrow.to_account_info()
-        },
-        signer,
-    );
+    emit!(TokenSold {
+        exhibition: ctx.accounts.exhibition.key(),
+        mint: ctx.accounts.deposited_token_mint.key(),
+        buyer: ctx.accounts.buyer.key(),
+        price,
+        amount_exhibitor: split.amount_exhibitor,
+        amount_renter: split.amount_renter,
+        amount_fee_earner: split.amount_fee_earner,
+        referrer: split.referrer,
+        amount_referrer: split.amount_referrer,
+    });
+
+    ctx.accounts.exhibition.stats.record_sale(price, &split)?;
+    ctx.accounts.state.stats.record_sale(price, &split)?;
+
+    let receipt = &mut ctx.accounts.sale_receipt;
+    receipt.mint = ctx.accounts.deposited_token_mint.key();
+    receipt.exhibition = ctx.accounts.exhibition.key();
+    receipt.property = ctx.accounts.exhibition.property;
+    receipt.buyer = ctx.accounts.buyer.key();
+    receipt.exhibitor = ctx.accounts.exhibitor.key();
+    receipt.price = price;
+    receipt.split = split;
+    receipt.timestamp = Clock::get()?.unix_timestamp;
+
+    ctx.accounts.provenance.n_sales = ctx.accounts.provenance.n_sales
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflowError)?;
 
-    token::transfer(context, 1)?;
+    let exhibition = &mut ctx.accounts.exhibition;
+    exhibition.n_pieces = exhibition.n_pieces
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathOverflowError)?;
+
+    release_token(
+        exhibition,
+        &ctx.accounts.escrow,
+        &ctx.accounts.deposited_token_account.to_account_info(),
+        &ctx.accounts.buyer_account.to_account_info(),
+        &ctx.accounts.deposit_payer.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+    )?;
 
     msg!("Token bought");
 
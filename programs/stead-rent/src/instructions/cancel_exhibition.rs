@@ -1,23 +1,42 @@
 use anchor_lang::prelude::*;
 
 use crate::state::*;
+use crate::errors::*;
 
 #[derive(Accounts)]
 pub struct CancelExhibition<'info> {
     /// The exhibition
-    #[account(mut)]
+    #[account(
+        mut,
+        has_one = renter @ ErrorCode::NotRenterError,
+        constraint = exhibition.status == ExhibitionStatus::Active @ ErrorCode::ExhibitionNotActiveError
+    )]
     pub exhibition: Account<'info, Exhibition>,
 
     /// The onwer of the propertry
-    #[account(constraint = renter.key() == exhibition.renter)]
-    pub renter: Signer<'info>,
+    pub renter: AccountInfo<'info>,
+
+    /// The renter or one of its managers
+    pub authority: Signer<'info>,
+
+    /// The permissions given by the renter to the authority, if not the renter
+    pub delegation: AccountInfo<'info>,
 }
 
-/// Buys a token from the exhibition and split revenues
-pub fn handler(
+/// Starts the notice period after which remaining pieces can be returned
+pub(crate) fn handler(
     ctx: Context<CancelExhibition>
 ) -> ProgramResult {
-    ctx.accounts.exhibition.status = ExhibitionStatus::Cancelled;
+    Delegation::authorize(
+        &ctx.accounts.renter.key(),
+        &ctx.accounts.authority.key(),
+        &ctx.accounts.delegation,
+        Delegation::CANCEL,
+    )?;
+
+    let exhibition = &mut ctx.accounts.exhibition;
+    exhibition.status = ExhibitionStatus::Cancelled;
+    exhibition.cancelled_at = Clock::get()?.unix_timestamp;
 
     msg!("Cancelled");
 
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct CancelProposal<'info> {
+    /// The global state
+    #[account(
+        seeds = [b"state"],
+        bump = state.bump
+    )]
+    pub state: Account<'info, State>,
+
+    /// The proposal, closed without being executed
+    #[account(
+        mut,
+        close = proposer,
+        has_one = proposer @ ErrorCode::NotProposerError,
+        seeds = [
+            b"proposal".as_ref(),
+            &proposal.index.to_le_bytes()
+        ],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    /// The admin who created the proposal, refunded for its account
+    #[account(mut)]
+    pub proposer: SystemAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+/// Closes a proposal that will not be executed
+/// The proposer can withdraw it at any time, anyone can clean it up once expired
+pub(crate) fn handler(ctx: Context<CancelProposal>) -> ProgramResult {
+    let proposal = &ctx.accounts.proposal;
+    let is_proposer = ctx.accounts.authority.key() == proposal.proposer;
+    if !is_proposer && Clock::get()?.unix_timestamp <= proposal.expires_at() {
+        return Err(ErrorCode::NotProposerError.into());
+    }
+
+    msg!("Proposal cancelled");
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct CloseBuyerRecord<'info> {
+    /// The exhibition the purchases were made in, which may already be closed
+    pub exhibition: AccountInfo<'info>,
+
+    /// The purchases being forgotten
+    #[account(
+        mut,
+        close = buyer,
+        has_one = exhibition @ ErrorCode::ExhibitionMismatchError,
+        has_one = buyer @ ErrorCode::NotBuyerError
+    )]
+    pub buyer_record: Account<'info, BuyerRecord>,
+
+    /// The wallet that made the purchases, refunded the rent of the record
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+}
+
+/// Lets a buyer recover the rent of its record once no presale can count its purchases
+pub(crate) fn handler(ctx: Context<CloseBuyerRecord>) -> ProgramResult {
+    // Records of a closed exhibition can always be closed
+    let exhibition = &ctx.accounts.exhibition;
+    if exhibition.owner == ctx.program_id && !exhibition.data_is_empty() {
+        let exhibition: Account<Exhibition> = Account::try_from(exhibition)?;
+        if let Some(presale) = &exhibition.presale {
+            if Clock::get()?.unix_timestamp < presale.end {
+                return Err(ErrorCode::PresaleRunningError.into());
+            }
+        }
+    }
+
+    msg!("Buyer record closed");
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+#[instruction(bump: u8)]
+pub struct CreateBundle<'info> {
+    /// The exhibition
+    #[account(mut, constraint = exhibition.status == ExhibitionStatus::Active @ ErrorCode::ExhibitionNotActiveError)]
+    pub exhibition: Account<'info, Exhibition>,
+
+    /// The bundle of items
+    #[account(
+        init,
+        payer = payer,
+        space = Bundle::LEN,
+        seeds = [
+            b"bundle".as_ref(),
+            exhibition.key().as_ref(),
+            &exhibition.n_bundles.to_le_bytes()
+        ],
+        bump = bump
+    )]
+    pub bundle: Account<'info, Bundle>,
+
+    /// The seller of the bundled items
+    pub exhibitor: Signer<'info>,
+
+    /// The account paying the transaction
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Groups items of the seller so that they are only sold together
+/// The bundled items are passed as remaining accounts
+pub(crate) fn handler(
+    ctx: Context<CreateBundle>,
+    _bump: u8,
+    price: u64
+) -> ProgramResult {
+    let items = ctx.remaining_accounts;
+    if items.len() < Bundle::MIN_ITEMS || items.len() > Bundle::MAX_ITEMS {
+        return Err(ErrorCode::InvalidBundleError.into());
+    }
+
+    let exhibition = &mut ctx.accounts.exhibition;
+    let bundle = &mut ctx.accounts.bundle;
+    bundle.exhibition = exhibition.key();
+    bundle.seller = ctx.accounts.exhibitor.key();
+    bundle.index = exhibition.n_bundles;
+    bundle.price = price;
+
+    for item in items {
+        let mut item: Account<ExhibitionItem> = Account::try_from(item)
+            .map_err(|_| ErrorCode::InvalidBundleError)?;
+        if !item.to_account_info().is_writable
+            || item.exhibition != bundle.exhibition
+            || item.seller != bundle.seller
+            || item.status != ItemStatus::Listed
+            || item.bundle.is_some()
+        {
+            return Err(ErrorCode::InvalidBundleError.into());
+        }
+
+        item.bundle = Some(bundle.key());
+        item.exit(ctx.program_id)?;
+        bundle.items.push(item.key());
+    }
+
+    exhibition.n_bundles = exhibition.n_bundles
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflowError)?;
+
+    msg!("Bundle created");
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+#[instruction(bump: u8)]
+pub struct CreateProposal<'info> {
+    /// The global state
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.bump
+    )]
+    pub state: Account<'info, State>,
+
+    /// The proposal
+    #[account(
+        init,
+        payer = proposer,
+        space = Proposal::LEN,
+        seeds = [
+            b"proposal".as_ref(),
+            &state.n_proposals.to_le_bytes()
+        ],
+        bump = bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    /// The admin submitting the proposal, which counts as its first approval
+    #[account(
+        mut,
+        constraint = state.is_admin(&proposer.key()) @ ErrorCode::NotAdminError
+    )]
+    pub proposer: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Submits a change of the state to the other admins
+pub(crate) fn handler(
+    ctx: Context<CreateProposal>,
+    bump: u8,
+    action: ProposalAction
+) -> ProgramResult {
+    action.validate()?;
+
+    let state = &mut ctx.accounts.state;
+    let proposer = ctx.accounts.proposer.key();
+    let now = Clock::get()?.unix_timestamp;
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.index = state.n_proposals;
+    proposal.bump = bump;
+    proposal.proposer = proposer;
+    proposal.action = action;
+    proposal.created_at = now;
+    proposal.approve(proposer, state, now)?;
+
+    state.n_proposals = state.n_proposals
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflowError)?;
+
+    msg!("Proposal created");
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+#[instruction(bump: u8)]
+pub struct Delegate<'info> {
+    /// The permissions given to the manager
+    #[account(
+        init_if_needed,
+        payer = principal,
+        space = Delegation::LEN,
+        seeds = [
+            b"delegation".as_ref(),
+            principal.key().as_ref(),
+            manager.key().as_ref()
+        ],
+        bump = bump
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    /// The renter or exhibitor giving the permissions
+    #[account(mut)]
+    pub principal: Signer<'info>,
+
+    /// The key allowed to act for the principal
+    pub manager: AccountInfo<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates or updates the permissions given to a manager
+pub(crate) fn handler(
+    ctx: Context<Delegate>,
+    _bump: u8,
+    permissions: u8,
+    expires_at: i64
+) -> ProgramResult {
+    if permissions & !Delegation::ALL != 0 {
+        return Err(ErrorCode::InvalidPermissionsError.into());
+    }
+
+    let delegation = &mut ctx.accounts.delegation;
+    delegation.principal = ctx.accounts.principal.key();
+    delegation.manager = ctx.accounts.manager.key();
+    delegation.permissions = permissions;
+    delegation.expires_at = expires_at;
+
+    msg!("Delegation set");
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct DelistLazyToken<'info> {
+    /// The global state
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.bump
+    )]
+    pub state: Account<'info, State>,
+
+    /// The exhibition
+    #[account(mut)]
+    pub exhibition: Account<'info, Exhibition>,
+
+    /// The listing being removed
+    #[account(
+        mut,
+        close = exhibitor,
+        has_one = exhibition @ ErrorCode::ExhibitionMismatchError,
+        constraint = lazy_listing.seller == exhibitor.key() @ ErrorCode::NotSellerError
+    )]
+    pub lazy_listing: Account<'info, LazyListing>,
+
+    /// The artist of the piece
+    #[account(mut)]
+    pub exhibitor: Signer<'info>,
+}
+
+/// Removes a piece that was never minted from the exhibition
+pub(crate) fn handler(
+    ctx: Context<DelistLazyToken>
+) -> ProgramResult {
+    let exhibition = &mut ctx.accounts.exhibition;
+    exhibition.n_pieces = exhibition.n_pieces
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathOverflowError)?;
+    exhibition.stats.record_withdrawal()?;
+    ctx.accounts.state.stats.record_withdrawal()?;
+
+    msg!("Token delisted");
+
+    Ok(())
+}
@@ -1,19 +1,31 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
 
 use crate::state::*;
+use crate::errors::*;
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct DepositTokenSeedBumps {
-    item: u8,
-    token_account: u8,
+    pub item: u8,
+    pub token_account: u8,
+    pub provenance: u8,
+    pub provenance_entry: u8,
 }
 
 #[derive(Accounts)]
 #[instruction(bumps: DepositTokenSeedBumps)]
 pub struct DepositToken<'info> {
+    /// The global state
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.bump,
+        constraint = !state.paused @ ErrorCode::PausedError
+    )]
+    pub state: Account<'info, State>,
+
     /// The exhibition
-    #[account(mut, has_one = exhibitor, constraint = exhibition.status == ExhibitionStatus::Active)]
+    #[account(mut, constraint = exhibition.status == ExhibitionStatus::Active @ ErrorCode::ExhibitionNotActiveError)]
     pub exhibition: Account<'info, Exhibition>,
 
     /// The item for sale in the exhibition
@@ -42,7 +54,7 @@ pub struct DepositToken<'info> {
 
     /// The mint of the deposited token
     #[account(mut)]
-    pub deposited_token_mint: AccountInfo<'info>,
+    pub deposited_token_mint: Account<'info, Mint>,
 
     /// The account that will hold the deposited token
     #[account(
@@ -58,24 +70,57 @@ pub struct DepositToken<'info> {
     )]
     pub deposited_token_account: Account<'info, TokenAccount>,
 
-    /// The owner of the deposited token
-    pub exhibitor: Signer<'info>,
+    /// The history of the deposited token
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = Provenance::LEN,
+        seeds = [
+            b"provenance".as_ref(),
+            deposited_token_mint.key().as_ref()
+        ],
+        bump = bumps.provenance
+    )]
+    pub provenance: Account<'info, Provenance>,
 
-    /// The exhibitor's account holding the deposited token
+    /// The record of the token entering this exhibition
+    #[account(
+        init,
+        payer = payer,
+        space = ProvenanceEntry::LEN,
+        seeds = [
+            b"provenance".as_ref(),
+            deposited_token_mint.key().as_ref(),
+            &provenance.n_exhibitions.to_le_bytes()
+        ],
+        bump = bumps.provenance_entry
+    )]
+    pub provenance_entry: Account<'info, ProvenanceEntry>,
+
+    /// The artist selling the deposited token
+    pub exhibitor: AccountInfo<'info>,
+
+    /// The artist or one of its managers
+    pub authority: Signer<'info>,
+
+    /// The permissions given by the artist to the authority, if not the artist
+    pub delegation: AccountInfo<'info>,
+
+    /// The authority's account holding the deposited token
     #[account(
         mut,
-        constraint =
-            exhibitor_account.mint == deposited_token_mint.key() &&
-            exhibitor_account.owner == exhibitor.key() &&
-            exhibitor_account.amount == 1
+        constraint = exhibitor_account.mint == deposited_token_mint.key() @ ErrorCode::MintMismatchError,
+        constraint = exhibitor_account.owner == authority.key() @ ErrorCode::TokenOwnerMismatchError,
+        constraint = exhibitor_account.amount == 1 @ ErrorCode::TokenAmountMismatchError
     )]
     pub exhibitor_account: Account<'info, TokenAccount>,
 
     /// The account paying the transaction
-    pub payer: AccountInfo<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
 
     /// The program for interacting with the token.
-    #[account(address = token::ID)]
+    #[account(address = token::ID @ ErrorCode::InvalidProgramError)]
     pub token_program: Program<'info, Token>,
 
     pub rent: Sysvar<'info, Rent>,
@@ -89,25 +134,75 @@ impl<'info> DepositToken<'info> {
             Transfer {
                 from: self.exhibitor_account.to_account_info(),
                 to: self.deposited_token_account.to_account_info(),
-                authority: self.exhibitor.to_account_info(),
+                authority: self.authority.to_account_info(),
             },
         )
     }
 }
 
-/// Creates an exhibition and
-pub fn handler(
+/// Deposits a piece of an allowed artist in the exhibition
+pub(crate) fn handler(
     ctx: Context<DepositToken>,
-    _bump: DepositTokenSeedBumps,
+    bumps: DepositTokenSeedBumps,
     price: u64,
+    currency: Currency,
+    metadata: ItemMetadata,
+    proof: Vec<[u8; 32]>,
 ) -> ProgramResult {
+    metadata.validate()?;
+    Delegation::authorize(
+        &ctx.accounts.exhibitor.key(),
+        &ctx.accounts.authority.key(),
+        &ctx.accounts.delegation,
+        Delegation::DEPOSIT,
+    )?;
+    ctx.accounts.exhibition.check_artist(
+        &ctx.accounts.exhibition.key(),
+        &ctx.accounts.exhibitor.key(),
+        &proof,
+        ctx.remaining_accounts,
+    )?;
+    ctx.accounts.state.check_collection(
+        &ctx.accounts.deposited_token_mint.key(),
+        ctx.remaining_accounts.last(),
+    )?;
+
     let exhibition = &mut ctx.accounts.exhibition;
-    exhibition.n_pieces += 1;
+    exhibition.n_pieces = exhibition.n_pieces
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflowError)?;
+    if exhibition.curated {
+        exhibition.n_pending = exhibition.n_pending
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflowError)?;
+    }
+    exhibition.stats.record_deposit()?;
+    ctx.accounts.state.stats.record_deposit()?;
 
     let item = &mut ctx.accounts.exhibition_item;
     item.exhibition = exhibition.key();
     item.mint = ctx.accounts.deposited_token_mint.key();
+    item.seller = ctx.accounts.exhibitor.key();
+    item.payer = ctx.accounts.payer.key();
     item.price = price;
+    item.currency = currency;
+    item.metadata = metadata;
+    item.status = if exhibition.curated { ItemStatus::Pending } else { ItemStatus::Listed };
+
+    let provenance = &mut ctx.accounts.provenance;
+    provenance.mint = item.mint;
+    provenance.bump = bumps.provenance;
+    provenance.n_exhibitions = provenance.n_exhibitions
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflowError)?;
+
+    let entry = &mut ctx.accounts.provenance_entry;
+    entry.mint = item.mint;
+    entry.exhibition = exhibition.key();
+    entry.property = exhibition.property;
+    entry.renter = exhibition.renter;
+    entry.exhibitor = item.seller;
+    entry.timestamp = Clock::get()?.unix_timestamp;
 
     token::transfer(ctx.accounts.transfer_context(), 1)?;
 
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct DissolveBundle<'info> {
+    /// The bundle being removed
+    #[account(
+        mut,
+        close = exhibitor,
+        has_one = exhibition @ ErrorCode::ExhibitionMismatchError,
+        constraint = bundle.seller == exhibitor.key() @ ErrorCode::NotSellerError
+    )]
+    pub bundle: Account<'info, Bundle>,
+
+    /// The exhibition of the bundled items
+    pub exhibition: Account<'info, Exhibition>,
+
+    /// The seller of the bundled items
+    #[account(mut)]
+    pub exhibitor: SystemAccount<'info>,
+
+    /// The seller, the renter, or anyone once a cancelled exhibition can be emptied
+    pub authority: Signer<'info>,
+}
+
+/// Lets the bundled items be sold or withdrawn individually again
+/// Every bundled item is passed as remaining account, in the order of the bundle
+pub(crate) fn handler(
+    ctx: Context<DissolveBundle>
+) -> ProgramResult {
+    // Bundled pieces must be released before they can be returned or rejected
+    let authority = ctx.accounts.authority.key();
+    let exhibition = &ctx.accounts.exhibition;
+    if authority != ctx.accounts.exhibitor.key()
+        && authority != exhibition.renter
+        && !exhibition.notice_elapsed(Clock::get()?.unix_timestamp)
+    {
+        return Err(ErrorCode::BundleLockedError.into());
+    }
+
+    let bundle = &ctx.accounts.bundle;
+    let items = ctx.remaining_accounts;
+    if items.len() != bundle.items.len() {
+        return Err(ErrorCode::InvalidBundleError.into());
+    }
+
+    for (key, item) in bundle.items.iter().zip(items.iter()) {
+        let mut item: Account<ExhibitionItem> = Account::try_from(item)
+            .map_err(|_| ErrorCode::InvalidBundleError)?;
+        if item.key() != *key || item.bundle != Some(bundle.key()) {
+            return Err(ErrorCode::InvalidBundleError.into());
+        }
+        item.bundle = None;
+        item.exit(ctx.program_id)?;
+    }
+
+    msg!("Bundle dissolved");
+
+    Ok(())
+}
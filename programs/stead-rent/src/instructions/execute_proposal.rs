@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    /// The global state
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.bump
+    )]
+    pub state: Account<'info, State>,
+
+    /// The proposal, closed once executed
+    #[account(
+        mut,
+        close = proposer,
+        has_one = proposer @ ErrorCode::NotProposerError,
+        seeds = [
+            b"proposal".as_ref(),
+            &proposal.index.to_le_bytes()
+        ],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    /// The admin who created the proposal, refunded for its account
+    #[account(mut)]
+    pub proposer: SystemAccount<'info>,
+}
+
+/// Applies an approved proposal to the state
+/// Anyone can execute the proposal once the threshold is reached and the timelock elapsed
+pub(crate) fn handler(ctx: Context<ExecuteProposal>) -> ProgramResult {
+    let state = &mut ctx.accounts.state;
+    let proposal = &ctx.accounts.proposal;
+
+    let executable_at = proposal.executable_at
+        .ok_or(ErrorCode::ThresholdNotReachedError)?;
+    if proposal.n_approvals(state) < state.threshold as usize {
+        return Err(ErrorCode::ThresholdNotReachedError.into());
+    }
+    let now = Clock::get()?.unix_timestamp;
+    if now < executable_at {
+        return Err(ErrorCode::TimelockError.into());
+    }
+    if now > proposal.expires_at() {
+        return Err(ErrorCode::ProposalExpiredError.into());
+    }
+
+    proposal.action.apply(state)?;
+
+    msg!("Proposal executed");
+
+    Ok(())
+}
@@ -1,11 +1,12 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
 
 use crate::state::*;
 use crate::errors::*;
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct InitExhibitionBumpSeeds {
+    pub stead: u8,
     pub exhibition: u8,
     pub escrow: u8,
     pub exhibition_token: u8
@@ -17,9 +18,23 @@ pub struct InitializeExhibition<'info> {
     /// The global state
     #[account(
         seeds = [b"state"],
-        bump = state.bump
+        bump = state.bump,
+        constraint = !state.paused @ ErrorCode::PausedError
     )]
     pub state: Account<'info, State>,
+
+    /// The rooms of the property
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = Stead::LEN,
+        seeds = [
+            b"stead".as_ref(),
+            exhibition_token_mint.key().as_ref()
+        ],
+        bump = bumps.stead
+    )]
+    pub stead: Account<'info, Stead>,
     
     /// The exhibition that will be created
     #[account(
@@ -28,7 +43,8 @@ pub struct InitializeExhibition<'info> {
         space = Exhibition::LEN,
         seeds = [
             b"exhibition".as_ref(),
-            exhibition_token_mint.key().as_ref()
+            exhibition_token_mint.key().as_ref(),
+            &stead.n_rooms.to_le_bytes()
         ],
         bump = bumps.exhibition
     )]
@@ -46,11 +62,11 @@ pub struct InitializeExhibition<'info> {
 
     /// The mint of the exposition token
     #[account(mut)]
-    pub exhibition_token_mint: AccountInfo<'info>,
+    pub exhibition_token_mint: Account<'info, Mint>,
 
-    /// The account that will hold the exhibition token
+    /// The account that will hold the exhibition token, shared by all rooms
     #[account(
-        init,
+        init_if_needed,
         seeds = [
             b"token_account".as_ref(),
             exhibition_token_mint.key().as_ref()
@@ -65,24 +81,23 @@ pub struct InitializeExhibition<'info> {
     /// The owner of the exhibition token
     pub renter: Signer<'info>,
 
-    /// The renter's account holding the exhibition token
+    /// The renter's account holding the exhibition token until the first room opens
     #[account(
         mut,
-        constraint = 
-            renter_account.mint == exhibition_token_mint.key() &&
-            renter_account.owner == renter.key() &&
-            renter_account.amount == 1
+        constraint = renter_account.mint == exhibition_token_mint.key() @ ErrorCode::MintMismatchError,
+        constraint = renter_account.owner == renter.key() @ ErrorCode::TokenOwnerMismatchError
     )]
     pub renter_account: Account<'info, TokenAccount>,
 
-    /// The exhibitor
+    /// The exhibitor, ignored for group shows
     pub exhibitor: AccountInfo<'info>,
 
     /// The account paying the transaction
-    pub payer: AccountInfo<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
 
     /// The program for interacting with the token.
-    #[account(address = token::ID)]
+    #[account(address = token::ID @ ErrorCode::InvalidProgramError)]
     pub token_program: Program<'info, Token>,
 
     pub rent: Sysvar<'info, Rent>,
@@ -102,26 +117,81 @@ impl<'info> InitializeExhibition<'info> {
     }
 }
 
-/// Creates an exhibition and 
-pub fn handler(
+/// Creates an exhibition and proposes its terms to the exhibitor
+/// Group shows have no single exhibitor and open right away
+pub(crate) fn handler(
     ctx: Context<InitializeExhibition>,
     bumps: InitExhibitionBumpSeeds,
-    renter_fee: u16
+    renter_fee: u16,
+    shares: Vec<Share>,
+    notice_period: i64,
+    deposit_policy: DepositPolicy,
+    curated: bool
 ) -> ProgramResult {
-    if renter_fee > 10000 - ctx.accounts.state.fee_amount {
+    if !(0..=Exhibition::MAX_NOTICE_PERIOD).contains(&notice_period) {
+        return Err(ErrorCode::InvalidNoticePeriodError.into());
+    }
+
+    if shares.len() > Exhibition::MAX_SHARES {
+        return Err(ErrorCode::TooManySharesError.into());
+    }
+
+    let total_fees = shares.iter().map(|share| share.basis_points as u32).sum::<u32>()
+        + renter_fee as u32
+        + ctx.accounts.state.fee_amount as u32;
+    if total_fees > 10000 {
         return Err(ErrorCode::FeeOutOfRangeError.into());
     }
 
+    let stead = &mut ctx.accounts.stead;
+    let escrow_property = stead.n_open_rooms == 0;
+    if escrow_property {
+        // A new renter starts with a single room
+        if stead.renter != ctx.accounts.renter.key() {
+            stead.max_rooms = 1;
+        }
+        stead.property = ctx.accounts.exhibition_token_mint.key();
+        stead.renter = ctx.accounts.renter.key();
+        stead.payer = ctx.accounts.payer.key();
+        stead.bump = bumps.stead;
+    } else if stead.renter != ctx.accounts.renter.key() {
+        return Err(ErrorCode::SteadRenterMismatchError.into());
+    }
+
+    if stead.n_open_rooms >= stead.max_rooms {
+        return Err(ErrorCode::RoomLimitReachedError.into());
+    }
+
     let exhibition = &mut ctx.accounts.exhibition;
 
+    exhibition.index = stead.n_rooms;
+    stead.n_rooms = stead.n_rooms
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflowError)?;
+    stead.n_open_rooms = stead.n_open_rooms
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflowError)?;
+
     exhibition.renter = ctx.accounts.renter.key();
     exhibition.property = ctx.accounts.renter_account.mint.key();
     exhibition.renter_fee = renter_fee;
-    exhibition.exhibitor = ctx.accounts.exhibitor.key();
-    exhibition.status = ExhibitionStatus::Active;
+    exhibition.shares = shares;
+    exhibition.deposit_policy = deposit_policy;
+    if exhibition.is_group_show() {
+        exhibition.exhibitor = Pubkey::default();
+        exhibition.status = ExhibitionStatus::Active;
+    } else {
+        exhibition.exhibitor = ctx.accounts.exhibitor.key();
+        exhibition.status = ExhibitionStatus::Proposed;
+    }
+    exhibition.notice_period = notice_period;
+    exhibition.curated = curated;
     exhibition.bumps = bumps;
 
-    token::transfer(ctx.accounts.transfer_context(), 1)?;
+    // Rooms share the property escrowed with the first one
+    if escrow_property {
+        token::transfer(ctx.accounts.transfer_context(), 1)?;
+    }
 
     msg!("Exhibition opened");
 
@@ -1,7 +1,6 @@
 use anchor_lang::prelude::*;
 
 use crate::state::*;
-use crate::errors::*;
 
 #[derive(Accounts)]
 #[instruction(bump: u8)]
@@ -17,27 +16,32 @@ pub struct InitializeState<'info> {
     pub state: Account<'info, State>,
 
     /// The wallet paying the transaction
-    pub payer: AccountInfo<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
 
     pub rent: Sysvar<'info, Rent>,
     pub system_program: Program<'info, System>,
 }
 
 /// Creates an exhibition and 
-pub fn handler(
+pub(crate) fn handler(
     ctx: Context<InitializeState>,
     bump: u8,
     fee_earner: Pubkey,
-    fee_amount: u16
+    fee_amount: u16,
+    referral_fee: u16,
+    min_fee: u64,
+    max_fee: Option<u64>
 ) -> ProgramResult {
-    if fee_amount > 10000 {
-        return Err(ErrorCode::FeeOutOfRangeError.into());
-    }
-    
+    State::check_fees(fee_amount, referral_fee, min_fee, max_fee)?;
+
     let state = &mut ctx.accounts.state;
     state.bump = bump;
     state.fee_earner = fee_earner;
     state.fee_amount = fee_amount;
+    state.referral_fee = referral_fee;
+    state.min_fee = min_fee;
+    state.max_fee = max_fee;
 
     msg!("State initialized");
 
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct InitializeMultisig<'info> {
+    /// The global state
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.bump,
+        constraint = !state.is_governed() @ ErrorCode::StateGovernedError
+    )]
+    pub state: Account<'info, State>,
+
+    #[account(constraint = owner.key() == state.fee_earner @ ErrorCode::NotFeeEarnerError)]
+    pub owner: Signer<'info>
+}
+
+/// Hands the state over to admins, who then change it through proposals
+pub(crate) fn handler(
+    ctx: Context<InitializeMultisig>,
+    admins: Vec<Pubkey>,
+    threshold: u8,
+    timelock: i64
+) -> ProgramResult {
+    State::check_admins(&admins, threshold, timelock)?;
+
+    let state = &mut ctx.accounts.state;
+    state.admins = admins;
+    state.threshold = threshold;
+    state.timelock = timelock;
+
+    msg!("Multisig initialized");
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+#[instruction(bump: u8)]
+pub struct ListLazyToken<'info> {
+    /// The global state
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.bump,
+        constraint = !state.paused @ ErrorCode::PausedError
+    )]
+    pub state: Account<'info, State>,
+
+    /// The exhibition
+    #[account(
+        mut,
+        constraint = exhibition.status == ExhibitionStatus::Active @ ErrorCode::ExhibitionNotActiveError,
+        constraint = !exhibition.curated @ ErrorCode::CuratedExhibitionError
+    )]
+    pub exhibition: Account<'info, Exhibition>,
+
+    /// The listing of the piece to be minted
+    #[account(
+        init,
+        payer = payer,
+        space = LazyListing::LEN,
+        seeds = [
+            b"lazy_listing".as_ref(),
+            exhibition.key().as_ref(),
+            &exhibition.n_lazy_listings.to_le_bytes()
+        ],
+        bump = bump
+    )]
+    pub lazy_listing: Account<'info, LazyListing>,
+
+    /// The artist of the piece
+    pub exhibitor: Signer<'info>,
+
+    /// The account paying the transaction
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Registers a piece that will be minted when bought
+pub(crate) fn handler(
+    ctx: Context<ListLazyToken>,
+    _bump: u8,
+    price: u64,
+    name: String,
+    symbol: String,
+    uri: String,
+    proof: Vec<[u8; 32]>,
+) -> ProgramResult {
+    ctx.accounts.exhibition.check_artist(
+        &ctx.accounts.exhibition.key(),
+        &ctx.accounts.exhibitor.key(),
+        &proof,
+        ctx.remaining_accounts,
+    )?;
+
+    let exhibition = &mut ctx.accounts.exhibition;
+
+    let listing = &mut ctx.accounts.lazy_listing;
+    listing.exhibition = exhibition.key();
+    listing.index = exhibition.n_lazy_listings;
+    listing.seller = ctx.accounts.exhibitor.key();
+    listing.price = price;
+    listing.name = name;
+    listing.symbol = symbol;
+    listing.uri = uri;
+    listing.validate()?;
+
+    exhibition.n_lazy_listings = exhibition.n_lazy_listings
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflowError)?;
+    exhibition.n_pieces = exhibition.n_pieces
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflowError)?;
+    exhibition.stats.record_deposit()?;
+    ctx.accounts.state.stats.record_deposit()?;
+
+    msg!("Token listed");
+
+    Ok(())
+}
@@ -1,17 +1,82 @@
+
 pub mod init_state;
 pub mod set_state;
+pub mod set_price_oracle;
+pub mod set_fee_recipients;
+pub mod set_allowed_collections;
 pub mod init_exhibition;
+pub mod accept_exhibition;
+pub mod withdraw_proposal;
 pub mod cancel_exhibition;
 pub mod close_exhibition;
 pub mod deposit_token;
+pub mod set_item_metadata;
+pub mod set_presale;
 pub mod withdraw_token;
 pub mod buy_token;
+pub mod list_lazy_token;
+pub mod delist_lazy_token;
+pub mod buy_lazy_token;
+pub mod set_ticketing;
+pub mod buy_ticket;
+pub mod return_token;
+pub mod return_lazy_listing;
+pub mod approve_artist;
+pub mod revoke_artist;
+pub mod approve_item;
+pub mod reject_item;
+pub mod set_room_limit;
+pub mod replace_exhibitor;
+pub mod delegate;
+pub mod revoke_delegation;
+pub mod set_item_price;
+pub mod create_bundle;
+pub mod dissolve_bundle;
+pub mod buy_bundle;
+pub mod initialize_multisig;
+pub mod create_proposal;
+pub mod approve_proposal;
+pub mod execute_proposal;
+pub mod cancel_proposal;
+pub mod close_buyer_record;
 
 pub use init_state::*;
 pub use set_state::*;
+pub use set_price_oracle::*;
+pub use set_fee_recipients::*;
+pub use set_allowed_collections::*;
 pub use init_exhibition::*;
+pub use accept_exhibition::*;
+pub use withdraw_proposal::*;
 pub use cancel_exhibition::*;
 pub use close_exhibition::*;
 pub use deposit_token::*;
+pub use set_item_metadata::*;
+pub use set_presale::*;
 pub use withdraw_token::*;
-pub use buy_token::*;
\ No newline at end of file
+pub use buy_token::*;
+pub use list_lazy_token::*;
+pub use delist_lazy_token::*;
+pub use buy_lazy_token::*;
+pub use set_ticketing::*;
+pub use buy_ticket::*;
+pub use return_token::*;
+pub use return_lazy_listing::*;
+pub use approve_artist::*;
+pub use revoke_artist::*;
+pub use approve_item::*;
+pub use reject_item::*;
+pub use set_room_limit::*;
+pub use replace_exhibitor::*;
+pub use delegate::*;
+pub use revoke_delegation::*;
+pub use set_item_price::*;
+pub use create_bundle::*;
+pub use dissolve_bundle::*;
+pub use buy_bundle::*;
+pub use initialize_multisig::*;
+pub use create_proposal::*;
+pub use approve_proposal::*;
+pub use execute_proposal::*;
+pub use cancel_proposal::*;
+pub use close_buyer_record::*;
\ No newline at end of file
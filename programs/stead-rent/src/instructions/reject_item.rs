@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+use crate::state::*;
+use crate::errors::*;
+use crate::sale::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RejectItemSeedBumps {
+    token_account: u8
+}
+
+#[derive(Accounts)]
+#[instruction(bumps: RejectItemSeedBumps)]
+pub struct RejectItem<'info> {
+    /// The global state
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.bump
+    )]
+    pub state: Account<'info, State>,
+
+    /// The exhibition
+    #[account(mut, has_one = renter @ ErrorCode::NotRenterError)]
+    pub exhibition: Account<'info, Exhibition>,
+
+    /// The seller of the item
+    #[account(mut, constraint = exhibitor.key() == exhibition_item.seller @ ErrorCode::NotSellerError)]
+    pub exhibitor: SystemAccount<'info>,
+
+    /// The seller's associated account that will receive the token
+    #[account(
+        init_if_needed,
+        payer = renter,
+        associated_token::mint = deposited_token_mint,
+        associated_token::authority = exhibitor
+    )]
+    pub exhibitor_account: Account<'info, TokenAccount>,
+
+    /// The item being removed
+    #[account(
+        mut,
+        close = exhibitor,
+        has_one = exhibition @ ErrorCode::ExhibitionMismatchError,
+        constraint = exhibition_item.mint == deposited_token_mint.key() @ ErrorCode::MintMismatchError,
+        constraint = exhibition_item.bundle.is_none() @ ErrorCode::ItemBundledError
+    )]
+    pub exhibition_item: Account<'info, ExhibitionItem>,
+
+    /// The account owning stored NFTs
+    #[account(
+        seeds = [
+            b"escrow".as_ref(),
+            exhibition.property.as_ref()
+        ],
+        bump = exhibition.bumps.escrow
+    )]
+    pub escrow: AccountInfo<'info>,
+
+    /// The mint of the token being removed
+    pub deposited_token_mint: Account<'info, Mint>,
+
+    /// The account that holds the token being removed
+    #[account(
+        mut,
+        seeds = [
+            b"token_account".as_ref(),
+            deposited_token_mint.key().as_ref()
+        ],
+        bump = bumps.token_account
+    )]
+    pub deposited_token_account: Account<'info, TokenAccount>,
+
+    /// The wallet that paid for the deposit, refunded when the token account is closed
+    #[account(mut, constraint = deposit_payer.key() == exhibition_item.payer @ ErrorCode::NotDepositPayerError)]
+    pub deposit_payer: SystemAccount<'info>,
+
+    /// The owner of the property
+    #[account(mut)]
+    pub renter: Signer<'info>,
+
+    /// The program for interacting with the token.
+    #[account(address = token::ID @ ErrorCode::InvalidProgramError)]
+    pub token_program: Program<'info, Token>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Sends a pending or listed item back to its seller
+pub(crate) fn handler(
+    ctx: Context<RejectItem>
+) -> ProgramResult {
+    let exhibition = &mut ctx.accounts.exhibition;
+    exhibition.remove_piece(&ctx.accounts.exhibition_item.status)?;
+    exhibition.stats.record_withdrawal()?;
+    ctx.accounts.state.stats.record_withdrawal()?;
+
+    release_token(
+        exhibition,
+        &ctx.accounts.escrow,
+        &ctx.accounts.deposited_token_account.to_account_info(),
+        &ctx.accounts.exhibitor_account.to_account_info(),
+        &ctx.accounts.deposit_payer.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+    )?;
+
+    msg!("Item rejected");
+
+    Ok(())
+}
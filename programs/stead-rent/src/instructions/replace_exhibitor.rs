@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct ReplaceExhibitor<'info> {
+    /// The global state
+    #[account(
+        seeds = [b"state"],
+        bump = state.bump
+    )]
+    pub state: Account<'info, State>,
+
+    /// The empty exhibition
+    #[account(
+        mut,
+        has_one = renter @ ErrorCode::NotRenterError,
+        constraint = exhibition.n_pieces == 0 @ ErrorCode::ExhibitionNotEmptyError,
+        constraint =
+            !exhibition.is_group_show() &&
+            exhibition.status != ExhibitionStatus::Cancelled
+            @ ErrorCode::ExhibitorNotReplaceableError
+    )]
+    pub exhibition: Account<'info, Exhibition>,
+
+    /// The owner of the property
+    pub renter: Signer<'info>,
+
+    /// The new exhibitor
+    pub exhibitor: AccountInfo<'info>,
+}
+
+/// Proposes the exhibition to another exhibitor, keeping its accounts and the escrowed stead
+/// The new exhibitor has to accept the terms before depositing
+pub(crate) fn handler(
+    ctx: Context<ReplaceExhibitor>,
+    renter_fee: Option<u16>
+) -> ProgramResult {
+    let exhibition = &mut ctx.accounts.exhibition;
+
+    if let Some(renter_fee) = renter_fee {
+        let total_fees = exhibition.shares.iter().map(|share| share.basis_points as u32).sum::<u32>()
+            + renter_fee as u32
+            + ctx.accounts.state.fee_amount as u32;
+        if total_fees > 10000 {
+            return Err(ErrorCode::FeeOutOfRangeError.into());
+        }
+
+        exhibition.renter_fee = renter_fee;
+    }
+
+    let previous_exhibitor = exhibition.exhibitor;
+    exhibition.exhibitor = ctx.accounts.exhibitor.key();
+    exhibition.status = ExhibitionStatus::Proposed;
+    // The presale was set up by the previous exhibitor
+    exhibition.presale = None;
+
+    emit!(ExhibitorReplaced {
+        exhibition: exhibition.key(),
+        previous_exhibitor,
+        exhibitor: exhibition.exhibitor,
+        renter_fee: exhibition.renter_fee,
+    });
+
+    msg!("Exhibitor replaced");
+
+    Ok(())
+}
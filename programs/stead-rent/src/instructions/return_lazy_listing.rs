@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct ReturnLazyListing<'info> {
+    /// The global state
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.bump
+    )]
+    pub state: Account<'info, State>,
+
+    /// The cancelled exhibition
+    #[account(mut)]
+    pub exhibition: Account<'info, Exhibition>,
+
+    /// The listing left in the exhibition
+    #[account(
+        mut,
+        close = exhibitor,
+        has_one = exhibition @ ErrorCode::ExhibitionMismatchError,
+        constraint = lazy_listing.seller == exhibitor.key() @ ErrorCode::NotSellerError
+    )]
+    pub lazy_listing: Account<'info, LazyListing>,
+
+    /// The artist of the piece
+    #[account(mut)]
+    pub exhibitor: SystemAccount<'info>,
+
+    /// Anyone removing the listing, such as the renter
+    pub cranker: Signer<'info>,
+}
+
+/// Removes a listing left in a cancelled exhibition
+pub(crate) fn handler(
+    ctx: Context<ReturnLazyListing>
+) -> ProgramResult {
+    let exhibition = &mut ctx.accounts.exhibition;
+    if !exhibition.notice_elapsed(Clock::get()?.unix_timestamp) {
+        return Err(ErrorCode::NoticePeriodNotElapsedError.into());
+    }
+
+    exhibition.n_pieces = exhibition.n_pieces
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathOverflowError)?;
+    exhibition.stats.record_withdrawal()?;
+    ctx.accounts.state.stats.record_withdrawal()?;
+
+    msg!("Listing returned");
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct RevokeArtist<'info> {
+    /// The group show
+    #[account(has_one = renter @ ErrorCode::NotRenterError)]
+    pub exhibition: Account<'info, Exhibition>,
+
+    /// The permission being removed
+    /// Pieces already deposited by the artist stay in the exhibition
+    #[account(
+        mut,
+        close = renter,
+        has_one = exhibition @ ErrorCode::ExhibitionMismatchError
+    )]
+    pub artist_approval: Account<'info, ArtistApproval>,
+
+    /// The owner of the property
+    #[account(mut)]
+    pub renter: Signer<'info>,
+}
+
+/// Prevents an artist from depositing more pieces in the exhibition
+pub(crate) fn handler(
+    _ctx: Context<RevokeArtist>
+) -> ProgramResult {
+    msg!("Artist revoked");
+
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct RevokeDelegation<'info> {
+    /// The permissions being removed
+    #[account(mut, close = principal, has_one = principal @ ErrorCode::NotPrincipalError)]
+    pub delegation: Account<'info, Delegation>,
+
+    /// The renter or exhibitor who gave the permissions
+    #[account(mut)]
+    pub principal: Signer<'info>,
+}
+
+/// Prevents a manager from acting for the principal
+pub(crate) fn handler(
+    _ctx: Context<RevokeDelegation>
+) -> ProgramResult {
+    msg!("Delegation revoked");
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct SetAllowedCollections<'info> {
+    /// The global state
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.bump,
+        constraint = !state.is_governed() @ ErrorCode::StateGovernedError
+    )]
+    pub state: Account<'info, State>,
+
+    #[account(constraint = owner.key() == state.fee_earner @ ErrorCode::NotFeeEarnerError)]
+    pub owner: Signer<'info>
+}
+
+/// Sets the collections whose tokens can be deposited, any collection being allowed when empty
+pub(crate) fn handler(
+    ctx: Context<SetAllowedCollections>,
+    collections: Vec<Pubkey>
+) -> ProgramResult {
+    State::check_allowed_collections(&collections)?;
+
+    ctx.accounts.state.allowed_collections = collections;
+
+    msg!("Allowed collections set");
+
+    Ok(())
+}
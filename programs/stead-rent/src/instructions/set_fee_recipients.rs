@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct SetFeeRecipients<'info> {
+    /// The global state
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.bump,
+        constraint = !state.is_governed() @ ErrorCode::StateGovernedError
+    )]
+    pub state: Account<'info, State>,
+
+    #[account(constraint = owner.key() == state.fee_earner @ ErrorCode::NotFeeEarnerError)]
+    pub owner: Signer<'info>
+}
+
+/// Sets the wallets sharing the fees, weighted in basis points of the fees
+pub(crate) fn handler(
+    ctx: Context<SetFeeRecipients>,
+    recipients: Vec<Share>
+) -> ProgramResult {
+    State::check_fee_recipients(&recipients)?;
+
+    ctx.accounts.state.fee_recipients = recipients;
+
+    msg!("Fee recipients set");
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct SetItemMetadata<'info> {
+    /// The exhibition
+    pub exhibition: Account<'info, Exhibition>,
+
+    /// The item being edited
+    #[account(
+        mut,
+        has_one = exhibition @ ErrorCode::ExhibitionMismatchError,
+        constraint = exhibition_item.seller == exhibitor.key() @ ErrorCode::NotSellerError
+    )]
+    pub exhibition_item: Account<'info, ExhibitionItem>,
+
+    /// The seller of the item
+    pub exhibitor: Signer<'info>,
+}
+
+/// Updates the display information of an item
+pub(crate) fn handler(
+    ctx: Context<SetItemMetadata>,
+    metadata: ItemMetadata
+) -> ProgramResult {
+    metadata.validate()?;
+
+    ctx.accounts.exhibition_item.metadata = metadata;
+
+    msg!("Item metadata set");
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct SetItemPrice<'info> {
+    /// The item being repriced
+    #[account(
+        mut,
+        constraint = exhibition_item.seller == exhibitor.key() @ ErrorCode::NotSellerError
+    )]
+    pub exhibition_item: Account<'info, ExhibitionItem>,
+
+    /// The seller of the item
+    pub exhibitor: AccountInfo<'info>,
+
+    /// The seller or one of its managers
+    pub authority: Signer<'info>,
+
+    /// The permissions given by the seller to the authority, if not the seller
+    pub delegation: AccountInfo<'info>,
+}
+
+/// Updates the price of an item
+pub(crate) fn handler(
+    ctx: Context<SetItemPrice>,
+    price: u64,
+    currency: Currency
+) -> ProgramResult {
+    Delegation::authorize(
+        &ctx.accounts.exhibitor.key(),
+        &ctx.accounts.authority.key(),
+        &ctx.accounts.delegation,
+        Delegation::REPRICE,
+    )?;
+
+    ctx.accounts.exhibition_item.price = price;
+    ctx.accounts.exhibition_item.currency = currency;
+
+    msg!("Item price set");
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct SetPresale<'info> {
+    /// The exhibition
+    #[account(mut)]
+    pub exhibition: Account<'info, Exhibition>,
+
+    /// The exhibitor, or the renter for group shows
+    #[account(
+        constraint =
+            authority.key() == exhibition.exhibitor ||
+            (exhibition.is_group_show() && authority.key() == exhibition.renter)
+            @ ErrorCode::UnauthorizedError
+    )]
+    pub authority: Signer<'info>,
+}
+
+/// Sets or removes the presale of the exhibition
+pub(crate) fn handler(
+    ctx: Context<SetPresale>,
+    presale: Option<Presale>
+) -> ProgramResult {
+    if let Some(presale) = &presale {
+        if presale.start >= presale.end {
+            return Err(ErrorCode::InvalidPresaleWindowError.into());
+        }
+    }
+
+    ctx.accounts.exhibition.presale = presale;
+
+    msg!("Presale set");
+
+    Ok(())
+}
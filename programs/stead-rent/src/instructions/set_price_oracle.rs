@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct SetPriceOracle<'info> {
+    /// The global state
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.bump,
+        constraint = !state.is_governed() @ ErrorCode::StateGovernedError
+    )]
+    pub state: Account<'info, State>,
+
+    #[account(constraint = owner.key() == state.fee_earner @ ErrorCode::NotFeeEarnerError)]
+    pub owner: Signer<'info>
+}
+
+/// Sets the price feed used to convert USD prices to lamports
+pub(crate) fn handler(
+    ctx: Context<SetPriceOracle>,
+    price_oracle: Pubkey,
+    max_price_age: i64,
+    max_confidence: u16
+) -> ProgramResult {
+    State::check_oracle(max_price_age, max_confidence)?;
+
+    let state = &mut ctx.accounts.state;
+    state.price_oracle = price_oracle;
+    state.max_price_age = max_price_age;
+    state.max_confidence = max_confidence;
+
+    msg!("Price oracle set");
+
+    Ok(())
+}
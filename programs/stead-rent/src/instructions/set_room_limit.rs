@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct SetRoomLimit<'info> {
+    /// The rooms of the property
+    #[account(mut, has_one = renter @ ErrorCode::NotRenterError)]
+    pub stead: Account<'info, Stead>,
+
+    /// The wallet that escrowed the property
+    pub renter: Signer<'info>,
+}
+
+/// Sets how many rooms can be open at the same time
+/// Rooms already open are kept when lowering the limit
+pub(crate) fn handler(
+    ctx: Context<SetRoomLimit>,
+    max_rooms: u16
+) -> ProgramResult {
+    if max_rooms == 0 {
+        return Err(ErrorCode::InvalidRoomLimitError.into());
+    }
+
+    ctx.accounts.stead.max_rooms = max_rooms;
+
+    msg!("Room limit set");
+
+    Ok(())
+}
@@ -10,26 +10,33 @@ pub struct SetState<'info> {
         mut,
         seeds = [b"state"],
         bump = state.bump,
+        constraint = !state.is_governed() @ ErrorCode::StateGovernedError
     )]
     pub state: Account<'info, State>,
 
-    #[account(constraint = owner.key() == state.fee_earner)]
+    #[account(constraint = owner.key() == state.fee_earner @ ErrorCode::NotFeeEarnerError)]
     pub owner: Signer<'info>
 }
 
 /// Creates an exhibition and 
-pub fn handler(
+pub(crate) fn handler(
     ctx: Context<SetState>,
     fee_earner: Pubkey,
-    fee_amount: u16
+    fee_amount: u16,
+    referral_fee: u16,
+    min_fee: u64,
+    max_fee: Option<u64>,
+    paused: bool
 ) -> ProgramResult {
-    if fee_amount > 10000 {
-        return Err(ErrorCode::FeeOutOfRangeError.into());
-    }
-    
+    State::check_fees(fee_amount, referral_fee, min_fee, max_fee)?;
+
     let state = &mut ctx.accounts.state;
     state.fee_earner = fee_earner;
     state.fee_amount = fee_amount;
+    state.referral_fee = referral_fee;
+    state.min_fee = min_fee;
+    state.max_fee = max_fee;
+    state.paused = paused;
 
     msg!("State set");
 
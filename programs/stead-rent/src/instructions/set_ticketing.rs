@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct SetTicketing<'info> {
+    /// The global state
+    #[account(
+        seeds = [b"state"],
+        bump = state.bump
+    )]
+    pub state: Account<'info, State>,
+
+    /// The exhibition
+    #[account(mut, has_one = renter @ ErrorCode::NotRenterError)]
+    pub exhibition: Account<'info, Exhibition>,
+
+    /// The owner of the property
+    pub renter: Signer<'info>,
+}
+
+/// Sets or removes the entrance fee of the exhibition
+pub(crate) fn handler(
+    ctx: Context<SetTicketing>,
+    ticketing: Option<Ticketing>
+) -> ProgramResult {
+    if let Some(ticketing) = &ticketing {
+        // Group shows have no single exhibitor to pay
+        if ctx.accounts.exhibition.is_group_show() && ticketing.exhibitor_fee != 0 {
+            return Err(ErrorCode::FeeOutOfRangeError.into());
+        }
+        if ticketing.exhibitor_fee as u32 + ctx.accounts.state.fee_amount as u32 > 10000 {
+            return Err(ErrorCode::FeeOutOfRangeError.into());
+        }
+    }
+
+    ctx.accounts.exhibition.ticketing = ticketing;
+
+    msg!("Ticketing set");
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Transfer};
+
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct WithdrawProposalSeedBumps {
+    exhibition_token: u8
+}
+
+#[derive(Accounts)]
+#[instruction(bumps: WithdrawProposalSeedBumps)]
+pub struct WithdrawProposal<'info> {
+    /// The rooms of the property
+    #[account(
+        mut,
+        seeds = [
+            b"stead".as_ref(),
+            exhibition.property.as_ref()
+        ],
+        bump = stead.bump
+    )]
+    pub stead: Account<'info, Stead>,
+
+    /// The proposed exhibition
+    #[account(mut, close = renter, has_one = renter @ ErrorCode::NotRenterError)]
+    pub exhibition: Account<'info, Exhibition>,
+
+    /// The renter or the exhibitor
+    #[account(
+        constraint =
+            authority.key() == exhibition.renter ||
+            authority.key() == exhibition.exhibitor
+            @ ErrorCode::UnauthorizedError
+    )]
+    pub authority: Signer<'info>,
+
+    /// The owner of the property
+    #[account(mut)]
+    pub renter: SystemAccount<'info>,
+
+    /// The renter's account that will receive the exhibition token once every room is closed
+    #[account(
+        mut,
+        constraint = renter_account.mint == exhibition.property @ ErrorCode::MintMismatchError,
+        constraint = renter_account.owner == renter.key() @ ErrorCode::TokenOwnerMismatchError
+    )]
+    pub renter_account: Account<'info, TokenAccount>,
+
+    /// The account owning stored NFTs
+    #[account(
+        seeds = [
+            b"escrow".as_ref(),
+            exhibition.property.as_ref()
+        ],
+        bump = exhibition.bumps.escrow
+    )]
+    pub escrow: AccountInfo<'info>,
+
+    /// The account that holds the exhibition token
+    #[account(
+        mut,
+        seeds = [
+            b"token_account".as_ref(),
+            exhibition.property.as_ref()
+        ],
+        bump = bumps.exhibition_token
+    )]
+    pub exhibition_token_account: Account<'info, TokenAccount>,
+
+    /// The wallet that paid for the account holding the exhibition token, refunded when it is closed
+    #[account(mut, constraint = deposit_payer.key() == stead.payer @ ErrorCode::NotDepositPayerError)]
+    pub deposit_payer: SystemAccount<'info>,
+
+    /// The program for interacting with the token.
+    #[account(address = token::ID @ ErrorCode::InvalidProgramError)]
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> WithdrawProposal<'info> {
+    fn transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.exhibition_token_account.to_account_info(),
+                to: self.renter_account.to_account_info(),
+                authority: self.escrow.clone(),
+            },
+        )
+    }
+
+    fn close_context(&self) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.exhibition_token_account.to_account_info(),
+                destination: self.deposit_payer.to_account_info(),
+                authority: self.escrow.clone(),
+            },
+        )
+    }
+}
+
+/// Closes the proposal, returning the exhibition token to the renter if no other room is open
+pub(crate) fn handler(
+    ctx: Context<WithdrawProposal>
+) -> ProgramResult {
+    let exhibition = &ctx.accounts.exhibition;
+    if exhibition.status != ExhibitionStatus::Proposed {
+        return Err(ErrorCode::NotProposedError.into());
+    }
+
+    ctx.accounts.stead.n_open_rooms = ctx.accounts.stead.n_open_rooms
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathOverflowError)?;
+    if ctx.accounts.stead.n_open_rooms == 0 {
+        let seeds = &[
+            b"escrow".as_ref(),
+            exhibition.property.as_ref(),
+            &[exhibition.bumps.escrow],
+        ];
+        let signer = &[&seeds[..]];
+
+        token::transfer(ctx.accounts.transfer_context().with_signer(signer), 1)?;
+        token::close_account(ctx.accounts.close_context().with_signer(signer))?;
+    }
+
+    msg!("Proposal withdrawn");
+
+    Ok(())
+}
@@ -1,30 +1,45 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
 
 use crate::state::*;
+use crate::errors::*;
+use crate::sale::*;
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct WithdrawTokenSeedBumps {
-    token_account: u8
+    pub token_account: u8
 }
 
 #[derive(Accounts)]
 #[instruction(bumps: WithdrawTokenSeedBumps)]
 pub struct WithdrawToken<'info> {
+    /// The global state
+    #[account(
+        mut,
+        seeds = [b"state"],
+        bump = state.bump
+    )]
+    pub state: Account<'info, State>,
+
     /// The exhibition
     #[account(mut)]
     pub exhibition: Account<'info, Exhibition>,
 
-    /// The exhibitor
-    #[account(mut, constraint = exhibitor.key() == exhibition.exhibitor)]
-    pub exhibitor: Signer<'info>,
+    /// The seller of the item
+    #[account(mut, constraint = exhibitor.key() == exhibition_item.seller @ ErrorCode::NotSellerError)]
+    pub exhibitor: SystemAccount<'info>,
+
+    /// The seller or one of its managers
+    pub authority: Signer<'info>,
 
-    /// The exhibitor's account that will receive the token
+    /// The permissions given by the seller to the authority, if not the seller
+    pub delegation: AccountInfo<'info>,
+
+    /// The seller's account that will receive the token
     #[account(
         mut,
-        constraint =
-            exhibitor_account.mint == deposited_token_mint.key() &&
-            exhibitor_account.owner == exhibitor.key()
+        constraint = exhibitor_account.mint == deposited_token_mint.key() @ ErrorCode::MintMismatchError,
+        constraint = exhibitor_account.owner == exhibitor.key() @ ErrorCode::TokenOwnerMismatchError
     )]
     pub exhibitor_account: Account<'info, TokenAccount>,
 
@@ -32,9 +47,9 @@ pub struct WithdrawToken<'info> {
     #[account(
         mut,
         close = exhibitor,
-        constraint = 
-            exhibition_item.exhibition == exhibition.key() &&
-            exhibition_item.mint == deposited_token_mint.key()
+        has_one = exhibition @ ErrorCode::ExhibitionMismatchError,
+        constraint = exhibition_item.mint == deposited_token_mint.key() @ ErrorCode::MintMismatchError,
+        constraint = exhibition_item.bundle.is_none() @ ErrorCode::ItemBundledError
     )]
     pub exhibition_item: Account<'info, ExhibitionItem>,
     
@@ -50,7 +65,7 @@ pub struct WithdrawToken<'info> {
 
     /// The mint of the token being withdrawn
     #[account(mut)]
-    pub deposited_token_mint: AccountInfo<'info>,
+    pub deposited_token_mint: Account<'info, Mint>,
 
     /// The account that holds the token being withdrawn
     #[account(
@@ -63,36 +78,39 @@ pub struct WithdrawToken<'info> {
     )]
     pub deposited_token_account: Account<'info, TokenAccount>,
 
+    /// The wallet that paid for the deposit, refunded when the token account is closed
+    #[account(mut, constraint = deposit_payer.key() == exhibition_item.payer @ ErrorCode::NotDepositPayerError)]
+    pub deposit_payer: SystemAccount<'info>,
+
     /// The program for interacting with the token.
-    #[account(address = token::ID)]
+    #[account(address = token::ID @ ErrorCode::InvalidProgramError)]
     pub token_program: Program<'info, Token>,
 }
 
-/// Buys a token from the exhibition and split revenues
-pub fn handler(
+/// Sends a token back to its seller
+pub(crate) fn handler(
     ctx: Context<WithdrawToken>
 ) -> ProgramResult {
+    Delegation::authorize(
+        &ctx.accounts.exhibitor.key(),
+        &ctx.accounts.authority.key(),
+        &ctx.accounts.delegation,
+        Delegation::WITHDRAW,
+    )?;
+
     let exhibition = &mut ctx.accounts.exhibition;
-    exhibition.n_pieces -= 1;
-
-    let seeds = &[
-        b"escrow".as_ref(),
-        exhibition.property.as_ref(),
-        &[exhibition.bumps.escrow],
-    ];
-    let signer = &[&seeds[..]];
-
-    let context = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        Transfer {
-            from: ctx.accounts.deposited_token_account.to_account_info(),
-            to: ctx.accounts.exhibitor_account.to_account_info(),
-            authority: ctx.accounts.escrow.to_account_info()
-        },
-        signer,
-    );
-
-    token::transfer(context, 1)?;
+    exhibition.remove_piece(&ctx.accounts.exhibition_item.status)?;
+    exhibition.stats.record_withdrawal()?;
+    ctx.accounts.state.stats.record_withdrawal()?;
+
+    release_token(
+        exhibition,
+        &ctx.accounts.escrow,
+        &ctx.accounts.deposited_token_account.to_account_info(),
+        &ctx.accounts.exhibitor_account.to_account_info(),
+        &ctx.accounts.deposit_payer.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+    )?;
 
     msg!("Token withdrawn");
 
@@ -3,10 +3,14 @@
 use anchor_lang::prelude::*;
 
 pub mod errors;
+pub mod events;
 pub mod instructions;
+pub mod oracle;
+pub mod sale;
 pub mod state;
 
 use instructions::*;
+use state::*;
 
 declare_id!("TrXDop6spRAwHDsSpvY51PxHkvZXKGNYC6bygXZLNC2");
 
@@ -15,49 +19,230 @@ mod stead_rent {
     use super::*;
 
     /// Initializes the state of the program
+    /// The fee taken on each sale is at least `min_fee` and at most `max_fee` lamports
     pub fn initialize_state(
         ctx: Context<InitializeState>,
         bump: u8,
         fee_earner: Pubkey,
         fee_amount: u16,
+        referral_fee: u16,
+        min_fee: u64,
+        max_fee: Option<u64>,
     ) -> ProgramResult {
-        instructions::init_state::handler(ctx, bump, fee_earner, fee_amount)
+        instructions::init_state::handler(
+            ctx,
+            bump,
+            fee_earner,
+            fee_amount,
+            referral_fee,
+            min_fee,
+            max_fee,
+        )
     }
 
-    /// Sets the state of the program
-    pub fn set_state(ctx: Context<SetState>, fee_earner: Pubkey, fee_amount: u16) -> ProgramResult {
-        instructions::set_state::handler(ctx, fee_earner, fee_amount)
+    /// Sets the state of the program, and pauses or resumes it
+    pub fn set_state(
+        ctx: Context<SetState>,
+        fee_earner: Pubkey,
+        fee_amount: u16,
+        referral_fee: u16,
+        min_fee: u64,
+        max_fee: Option<u64>,
+        paused: bool,
+    ) -> ProgramResult {
+        instructions::set_state::handler(
+            ctx,
+            fee_earner,
+            fee_amount,
+            referral_fee,
+            min_fee,
+            max_fee,
+            paused,
+        )
+    }
+
+    /// Lets the fee earner set the price feed converting USD prices to lamports
+    pub fn set_price_oracle(
+        ctx: Context<SetPriceOracle>,
+        price_oracle: Pubkey,
+        max_price_age: i64,
+        max_confidence: u16,
+    ) -> ProgramResult {
+        instructions::set_price_oracle::handler(ctx, price_oracle, max_price_age, max_confidence)
+    }
+
+    /// Lets the fee earner split the fees between several wallets
+    /// Fees go back to the fee earner when the list is empty
+    pub fn set_fee_recipients(
+        ctx: Context<SetFeeRecipients>,
+        recipients: Vec<Share>,
+    ) -> ProgramResult {
+        instructions::set_fee_recipients::handler(ctx, recipients)
+    }
+
+    /// Lets the fee earner restrict deposits to tokens of some collections
+    /// Every collection is allowed when the list is empty
+    pub fn set_allowed_collections(
+        ctx: Context<SetAllowedCollections>,
+        collections: Vec<Pubkey>,
+    ) -> ProgramResult {
+        instructions::set_allowed_collections::handler(ctx, collections)
+    }
+
+    /// Lets the fee earner hand the state over to admins
+    /// From then on, the state only changes through proposals approved by the admins
+    pub fn initialize_multisig(
+        ctx: Context<InitializeMultisig>,
+        admins: Vec<Pubkey>,
+        threshold: u8,
+        timelock: i64,
+    ) -> ProgramResult {
+        instructions::initialize_multisig::handler(ctx, admins, threshold, timelock)
+    }
+
+    /// Lets an admin propose a change of the state
+    pub fn create_proposal(
+        ctx: Context<CreateProposal>,
+        bump: u8,
+        action: ProposalAction,
+    ) -> ProgramResult {
+        instructions::create_proposal::handler(ctx, bump, action)
+    }
+
+    /// Lets an admin approve a proposal
+    pub fn approve_proposal(ctx: Context<ApproveProposal>) -> ProgramResult {
+        instructions::approve_proposal::handler(ctx)
+    }
+
+    /// Executes a proposal approved by enough admins, once its timelock elapsed
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> ProgramResult {
+        instructions::execute_proposal::handler(ctx)
+    }
+
+    /// Lets the proposer withdraw a proposal, or anyone close it once expired
+    pub fn cancel_proposal(ctx: Context<CancelProposal>) -> ProgramResult {
+        instructions::cancel_proposal::handler(ctx)
     }
 
     /// Creates an exhibition, held by a token
+    /// Each exhibition is a room of the property, which is escrowed with the first room
+    /// The exhibition starts once the exhibitor accepted its terms
+    /// Group shows, open to several artists, start right away
+    /// Pieces deposited in curated exhibitions are sold once approved by the renter
     pub fn initialize_exhibition(
         ctx: Context<InitializeExhibition>,
         bumps: InitExhibitionBumpSeeds,
         renter_fee: u16,
+        shares: Vec<Share>,
+        notice_period: i64,
+        deposit_policy: DepositPolicy,
+        curated: bool,
     ) -> ProgramResult {
-        instructions::init_exhibition::handler(ctx, bumps, renter_fee)
+        instructions::init_exhibition::handler(
+            ctx,
+            bumps,
+            renter_fee,
+            shares,
+            notice_period,
+            deposit_policy,
+            curated,
+        )
+    }
+
+    /// Lets the exhibitor accept the terms of a proposed exhibition
+    pub fn accept_exhibition(ctx: Context<AcceptExhibition>) -> ProgramResult {
+        instructions::accept_exhibition::handler(ctx)
+    }
+
+    /// Lets the renter or the exhibitor withdraw a proposed exhibition
+    /// The stead is released if no other room is open
+    pub fn withdraw_proposal(
+        ctx: Context<WithdrawProposal>,
+        _bumps: WithdrawProposalSeedBumps,
+    ) -> ProgramResult {
+        instructions::withdraw_proposal::handler(ctx)
     }
 
     /// Prevents the artist from depositing more items
+    /// Can be done by the renter or one of its managers
     pub fn cancel_exhibition(ctx: Context<CancelExhibition>) -> ProgramResult {
         instructions::cancel_exhibition::handler(ctx)
     }
 
+    /// Lets a renter or an exhibitor allow a manager to deposit, withdraw, reprice or cancel for them
+    /// Updates the permissions if the manager already has some
+    pub fn delegate(
+        ctx: Context<Delegate>,
+        bump: u8,
+        permissions: u8,
+        expires_at: i64,
+    ) -> ProgramResult {
+        instructions::delegate::handler(ctx, bump, permissions, expires_at)
+    }
+
+    /// Lets a renter or an exhibitor remove the permissions given to a manager
+    pub fn revoke_delegation(ctx: Context<RevokeDelegation>) -> ProgramResult {
+        instructions::revoke_delegation::handler(ctx)
+    }
+
+    /// Lets the renter hand an empty exhibition to another artist, optionally changing its fee
+    /// The new exhibitor accepts the terms the same way as for a new exhibition
+    pub fn replace_exhibitor(
+        ctx: Context<ReplaceExhibitor>,
+        renter_fee: Option<u16>,
+    ) -> ProgramResult {
+        instructions::replace_exhibitor::handler(ctx, renter_fee)
+    }
+
     /// Closes the exhibition to enable opening a new one with another artist
+    /// The stead is released once every room is closed
     pub fn close_exhibition(ctx: Context<CloseExhibition>) -> ProgramResult {
         instructions::close_exhibition::handler(ctx)
     }
 
-    /// Lets the exhibitor deposit tokens in the exhibition
+    /// Lets an allowed artist, or one of its managers, deposit tokens in the exhibition
+    /// Artists of allowlisted group shows prove they are part of the allowlist,
+    /// while approved artists pass their approval as remaining account
+    /// When collections are restricted, the Metaplex metadata of the token comes last
     pub fn deposit_token(
         ctx: Context<DepositToken>,
         bumps: DepositTokenSeedBumps,
         price: u64,
+        currency: Currency,
+        metadata: ItemMetadata,
+        proof: Vec<[u8; 32]>,
+    ) -> ProgramResult {
+        instructions::deposit_token::handler(ctx, bumps, price, currency, metadata, proof)
+    }
+
+    /// Lets the seller edit how a deposited token is displayed
+    pub fn set_item_metadata(
+        ctx: Context<SetItemMetadata>,
+        metadata: ItemMetadata,
+    ) -> ProgramResult {
+        instructions::set_item_metadata::handler(ctx, metadata)
+    }
+
+    /// Lets the seller, or one of its managers, change the price of a deposited token
+    pub fn set_item_price(
+        ctx: Context<SetItemPrice>,
+        price: u64,
+        currency: Currency,
     ) -> ProgramResult {
-        instructions::deposit_token::handler(ctx, bumps, price)
+        instructions::set_item_price::handler(ctx, price, currency)
+    }
+
+    /// Lets the exhibitor, or the renter of a group show, restrict early purchases to an allowlist of buyers
+    pub fn set_presale(ctx: Context<SetPresale>, presale: Option<Presale>) -> ProgramResult {
+        instructions::set_presale::handler(ctx, presale)
     }
 
-    /// Lets the exhibitor withdraw tokens from the exhibition
+    /// Lets a buyer close its purchase record once the presale of the exhibition is over
+    pub fn close_buyer_record(ctx: Context<CloseBuyerRecord>) -> ProgramResult {
+        instructions::close_buyer_record::handler(ctx)
+    }
+
+    /// Lets the seller, or one of its managers, withdraw a token from the exhibition
     pub fn withdraw_token(
         ctx: Context<WithdrawToken>,
         _bumps: WithdrawTokenSeedBumps,
@@ -66,7 +251,124 @@ mod stead_rent {
     }
 
     /// Lets anyone buy one of the token deposited in the exhibition
-    pub fn buy_token(ctx: Context<BuyToken>, bumps: BuyTokenSeedBumps) -> ProgramResult {
-        instructions::buy_token::handler(ctx, bumps)
+    /// The recipients of the exhibition shares are passed as remaining accounts, then the
+    /// fee recipients of the state, optionally followed by the referrer of the buyer
+    /// During a presale, the buyer must prove it is part of the allowlist
+    /// Prices in USD are converted with the oracle, the buyer pays at most `max_lamports`
+    pub fn buy_token<'info>(
+        ctx: Context<'_, '_, '_, 'info, BuyToken<'info>>,
+        bumps: BuyTokenSeedBumps,
+        proof: Vec<[u8; 32]>,
+        max_lamports: u64,
+    ) -> ProgramResult {
+        instructions::buy_token::handler(ctx, bumps, proof, max_lamports)
+    }
+
+    /// Lets the seller group listed items so that they are only sold together
+    /// The bundled items are passed as remaining accounts
+    pub fn create_bundle(ctx: Context<CreateBundle>, bump: u8, price: u64) -> ProgramResult {
+        instructions::create_bundle::handler(ctx, bump, price)
+    }
+
+    /// Lets the seller or the renter sell or remove the bundled items individually again,
+    /// and anyone once the exhibition is cancelled and its notice period elapsed
+    /// The bundled items are passed as remaining accounts
+    pub fn dissolve_bundle(ctx: Context<DissolveBundle>) -> ProgramResult {
+        instructions::dissolve_bundle::handler(ctx)
+    }
+
+    /// Lets anyone buy every item of a bundle at once
+    /// Each bundled item is passed as remaining account, followed by the account holding its token,
+    /// the buyer's account that will receive it, the wallet that paid for its deposit,
+    /// its provenance and its new sale receipt, then come the same accounts as when buying a token
+    pub fn buy_bundle<'info>(
+        ctx: Context<'_, '_, '_, 'info, BuyBundle<'info>>,
+    ) -> ProgramResult {
+        instructions::buy_bundle::handler(ctx)
+    }
+
+    /// Lets an allowed artist list a piece that is minted when bought
+    /// Artists are checked the same way as when depositing a token
+    pub fn list_lazy_token(
+        ctx: Context<ListLazyToken>,
+        bump: u8,
+        price: u64,
+        name: String,
+        symbol: String,
+        uri: String,
+        proof: Vec<[u8; 32]>,
+    ) -> ProgramResult {
+        instructions::list_lazy_token::handler(ctx, bump, price, name, symbol, uri, proof)
+    }
+
+    /// Lets the seller remove a piece that was never minted
+    pub fn delist_lazy_token(ctx: Context<DelistLazyToken>) -> ProgramResult {
+        instructions::delist_lazy_token::handler(ctx)
+    }
+
+    /// Lets anyone buy a listed piece, minting it in the process
+    /// Remaining accounts are the same as when buying a token
+    pub fn buy_lazy_token<'info>(
+        ctx: Context<'_, '_, '_, 'info, BuyLazyToken<'info>>,
+        bumps: BuyLazyTokenSeedBumps,
+        create_metadata: bool,
+        proof: Vec<[u8; 32]>,
+    ) -> ProgramResult {
+        instructions::buy_lazy_token::handler(ctx, bumps, create_metadata, proof)
+    }
+
+    /// Lets the renter charge visitors an entrance fee
+    pub fn set_ticketing(
+        ctx: Context<SetTicketing>,
+        ticketing: Option<Ticketing>,
+    ) -> ProgramResult {
+        instructions::set_ticketing::handler(ctx, ticketing)
+    }
+
+    /// Lets anyone buy a ticket to enter the exhibition
+    /// The fee recipients of the state are passed as remaining accounts
+    pub fn buy_ticket<'info>(
+        ctx: Context<'_, '_, '_, 'info, BuyTicket<'info>>,
+        bump: u8,
+    ) -> ProgramResult {
+        instructions::buy_ticket::handler(ctx, bump)
+    }
+
+    /// Lets anyone return a token to its seller once the notice period of a cancellation elapsed
+    pub fn return_token(
+        ctx: Context<ReturnToken>,
+        _bumps: ReturnTokenSeedBumps,
+    ) -> ProgramResult {
+        instructions::return_token::handler(ctx)
+    }
+
+    /// Lets anyone remove a listing once the notice period of a cancellation elapsed
+    pub fn return_lazy_listing(ctx: Context<ReturnLazyListing>) -> ProgramResult {
+        instructions::return_lazy_listing::handler(ctx)
+    }
+
+    /// Lets the renter allow an artist to deposit in a group show
+    pub fn approve_artist(ctx: Context<ApproveArtist>, bump: u8) -> ProgramResult {
+        instructions::approve_artist::handler(ctx, bump)
+    }
+
+    /// Lets the renter withdraw the permission given to an artist
+    pub fn revoke_artist(ctx: Context<RevokeArtist>) -> ProgramResult {
+        instructions::revoke_artist::handler(ctx)
+    }
+
+    /// Lets the renter set how many rooms the stead can host at the same time
+    pub fn set_room_limit(ctx: Context<SetRoomLimit>, max_rooms: u16) -> ProgramResult {
+        instructions::set_room_limit::handler(ctx, max_rooms)
+    }
+
+    /// Lets the renter of a curated exhibition put a pending piece up for sale
+    pub fn approve_item(ctx: Context<ApproveItem>) -> ProgramResult {
+        instructions::approve_item::handler(ctx)
+    }
+
+    /// Lets the renter send a pending or listed piece back to its seller
+    pub fn reject_item(ctx: Context<RejectItem>, _bumps: RejectItemSeedBumps) -> ProgramResult {
+        instructions::reject_item::handler(ctx)
     }
 }
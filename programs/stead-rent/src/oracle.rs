@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use std::convert::TryInto;
+
+use crate::state::*;
+use crate::errors::*;
+
+const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+const PYTH_VERSION: u32 = 2;
+const PYTH_PRICE_ACCOUNT: u32 = 3;
+const PYTH_STATUS_TRADING: u32 = 1;
+
+/// The aggregate price of a Pyth price account
+pub struct OraclePrice {
+    /// The price of one SOL in USD, scaled by `10^expo`
+    pub price: i64,
+
+    /// The confidence interval around the price, with the same scale
+    pub conf: u64,
+
+    /// The exponent of the price
+    pub expo: i32,
+
+    /// The time at which the price was published
+    pub timestamp: i64,
+}
+
+fn read<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N]> {
+    data.get(offset..offset + N)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| ErrorCode::InvalidOracleError.into())
+}
+
+impl OraclePrice {
+    /// Reads the aggregate price of a Pyth price account
+    pub fn load(account: &AccountInfo) -> Result<OraclePrice> {
+        let data = account.try_borrow_data()?;
+
+        let magic = u32::from_le_bytes(read(&data, 0)?);
+        let version = u32::from_le_bytes(read(&data, 4)?);
+        let account_type = u32::from_le_bytes(read(&data, 8)?);
+        if magic != PYTH_MAGIC || version != PYTH_VERSION || account_type != PYTH_PRICE_ACCOUNT {
+            return Err(ErrorCode::InvalidOracleError.into());
+        }
+
+        let status = u32::from_le_bytes(read(&data, 224)?);
+        if status != PYTH_STATUS_TRADING {
+            return Err(ErrorCode::StalePriceError.into());
+        }
+
+        Ok(OraclePrice {
+            expo: i32::from_le_bytes(read(&data, 20)?),
+            timestamp: i64::from_le_bytes(read(&data, 96)?),
+            price: i64::from_le_bytes(read(&data, 208)?),
+            conf: u64::from_le_bytes(read(&data, 216)?),
+        })
+    }
+
+    /// Converts an amount of USD cents to lamports
+    pub fn usd_cents_to_lamports(&self, cents: u64) -> Result<u64> {
+        if self.price <= 0 {
+            return Err(ErrorCode::InvalidOracleError.into());
+        }
+
+        // lamports = cents / 100 * 10^9 / (price * 10^expo)
+        let mut numerator = cents as u128 * 10u128.pow(9);
+        let mut denominator = self.price as u128 * 100;
+        let scale = 10u128
+            .checked_pow(self.expo.unsigned_abs())
+            .ok_or(ErrorCode::InvalidOracleError)?;
+        if self.expo < 0 {
+            numerator = numerator.checked_mul(scale).ok_or(ErrorCode::InvalidOracleError)?;
+        } else {
+            denominator = denominator.checked_mul(scale).ok_or(ErrorCode::InvalidOracleError)?;
+        }
+
+        (numerator / denominator)
+            .try_into()
+            .map_err(|_| ErrorCode::InvalidOracleError.into())
+    }
+}
+
+/// Computes the amount of lamports paid for an item
+/// USD prices are converted with the oracle whitelisted in the state
+pub fn price_in_lamports(
+    state: &State,
+    item: &ExhibitionItem,
+    oracle: &AccountInfo,
+) -> Result<u64> {
+    match item.currency {
+        Currency::Lamports => Ok(item.price),
+        Currency::UsdCents => {
+            let price = OraclePrice::load(oracle)?;
+
+            let age = Clock::get()?.unix_timestamp - price.timestamp;
+            if age > state.max_price_age {
+                return Err(ErrorCode::StalePriceError.into());
+            }
+            if price.conf as u128 * 10000 > price.price.unsigned_abs() as u128 * state.max_confidence as u128 {
+                return Err(ErrorCode::PriceUncertainError.into());
+            }
+
+            price.usd_cents_to_lamports(item.price)
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, Transfer};
+
+use crate::state::*;
+use crate::errors::*;
+
+/// The wallets receiving the proceeds of a sale
+pub struct Payees<'a, 'info> {
+    /// The owner of the piece being sold
+    pub exhibitor: &'a AccountInfo<'info>,
+
+    /// The wallet renting the property
+    pub renter: &'a AccountInfo<'info>,
+
+    /// The DAO taking a cut
+    pub dao: &'a AccountInfo<'info>,
+
+    /// The recipients of the exhibition shares, then the fee recipients of the state,
+    /// optionally followed by the referrer
+    pub others: &'a [AccountInfo<'info>],
+}
+
+/// Transfers lamports from the buyer to a recipient
+pub fn pay<'info>(
+    buyer: &AccountInfo<'info>,
+    recipient: &AccountInfo<'info>,
+    amount: u64,
+) -> ProgramResult {
+    let ix = anchor_lang::solana_program::system_instruction::transfer(
+        buyer.key,
+        recipient.key,
+        amount,
+    );
+    anchor_lang::solana_program::program::invoke(&ix, &[buyer.clone(), recipient.clone()])
+}
+
+/// Sends a token out of the escrow of an exhibition, then closes the emptied token account
+/// and refunds its rent to the wallet that paid for it
+pub fn release_token<'info>(
+    exhibition: &Exhibition,
+    escrow: &AccountInfo<'info>,
+    token_account: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    rent_receiver: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+) -> ProgramResult {
+    let seeds = &[
+        b"escrow".as_ref(),
+        exhibition.property.as_ref(),
+        &[exhibition.bumps.escrow],
+    ];
+    let signer = &[&seeds[..]];
+
+    let context = CpiContext::new_with_signer(
+        token_program.clone(),
+        Transfer {
+            from: token_account.clone(),
+            to: destination.clone(),
+            authority: escrow.clone(),
+        },
+        signer,
+    );
+    token::transfer(context, 1)?;
+
+    let context = CpiContext::new_with_signer(
+        token_program.clone(),
+        CloseAccount {
+            account: token_account.clone(),
+            destination: rent_receiver.clone(),
+            authority: escrow.clone(),
+        },
+        signer,
+    );
+    token::close_account(context)
+}
+
+/// Computes the cut of a price given in basis points
+pub fn cut(price: u64, basis_points: u16) -> u64 {
+    // At most 10000 basis points, the cut never exceeds the price
+    (price as u128 * basis_points as u128 / 10000) as u64
+}
+
+/// Transfers the fees to the fee earner, or splits them between the fee recipients
+pub fn pay_fees<'info>(
+    state: &State,
+    buyer: &AccountInfo<'info>,
+    dao: &AccountInfo<'info>,
+    recipients: &[AccountInfo<'info>],
+    amount: u64,
+) -> ProgramResult {
+    if state.fee_recipients.is_empty() {
+        return pay(buyer, dao, amount);
+    }
+    if recipients.len() != state.fee_recipients.len() {
+        return Err(ErrorCode::FeeRecipientMismatchError.into());
+    }
+
+    // The first recipient also gets what is left after rounding down the other cuts
+    let mut amount_first = amount;
+    for (share, recipient) in state.fee_recipients.iter().zip(recipients.iter()).skip(1) {
+        if recipient.key() != share.recipient || !recipient.is_writable {
+            return Err(ErrorCode::FeeRecipientMismatchError.into());
+        }
+
+        let amount_recipient = cut(amount, share.basis_points);
+        amount_first = amount_first
+            .checked_sub(amount_recipient)
+            .ok_or(ErrorCode::MathOverflowError)?;
+        pay(buyer, recipient, amount_recipient)?;
+    }
+
+    let first = &recipients[0];
+    if first.key() != state.fee_recipients[0].recipient || !first.is_writable {
+        return Err(ErrorCode::FeeRecipientMismatchError.into());
+    }
+    pay(buyer, first, amount_first)
+}
+
+/// Splits the price of a piece between the parties of the exhibition
+pub fn split_sale<'info>(
+    state: &State,
+    exhibition: &Exhibition,
+    buyer: &AccountInfo<'info>,
+    payees: Payees<'_, 'info>,
+    price: u64,
+) -> Result<SaleSplit> {
+    let amount_renter = cut(price, exhibition.renter_fee);
+    let mut amount_fee_earner = state.fee(price);
+    let mut amount_exhibitor = price
+        .checked_sub(amount_fee_earner)
+        .and_then(|amount| amount.checked_sub(amount_renter))
+        .ok_or_else(|| state.shortfall_error(price))?;
+    let mut amount_shares: u64 = 0;
+
+    let shares = &exhibition.shares;
+    let n_recipients = shares.len() + state.fee_recipients.len();
+    if payees.others.len() < n_recipients || payees.others.len() > n_recipients + 1 {
+        return Err(ErrorCode::ShareRecipientMismatchError.into());
+    }
+    let (recipients, referrer) = payees.others.split_at(n_recipients);
+    let (recipients, fee_recipients) = recipients.split_at(shares.len());
+
+    // Transfer to each share recipient
+    for (share, recipient) in shares.iter().zip(recipients.iter()) {
+        if recipient.key() != share.recipient || !recipient.is_writable {
+            return Err(ErrorCode::ShareRecipientMismatchError.into());
+        }
+
+        let amount = cut(price, share.basis_points);
+        amount_exhibitor = amount_exhibitor
+            .checked_sub(amount)
+            .ok_or_else(|| state.shortfall_error(price))?;
+        amount_shares = amount_shares
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflowError)?;
+        pay(buyer, recipient, amount)?;
+    }
+
+    // Transfer to the referrer, out of the fee earner's cut
+    let referrer = referrer.first();
+    let mut amount_referrer = 0;
+    if let Some(referrer) = referrer {
+        // The parties of the sale cannot refer it to themselves
+        let parties = [buyer.key(), payees.exhibitor.key(), payees.renter.key()];
+        if parties.contains(&referrer.key()) || !referrer.is_writable {
+            return Err(ErrorCode::InvalidReferrerError.into());
+        }
+
+        // A capped fee bounds the referral fee too
+        amount_referrer = cut(price, state.referral_fee).min(amount_fee_earner);
+        amount_fee_earner = amount_fee_earner
+            .checked_sub(amount_referrer)
+            .ok_or(ErrorCode::MathOverflowError)?;
+        pay(buyer, referrer, amount_referrer)?;
+    }
+
+    // Transfer to the exhibitor
+    pay(buyer, payees.exhibitor, amount_exhibitor)?;
+    // Transfer to the renter
+    pay(buyer, payees.renter, amount_renter)?;
+    // Transfer to the fee earner
+    pay_fees(state, buyer, payees.dao, fee_recipients, amount_fee_earner)?;
+
+    Ok(SaleSplit {
+        amount_exhibitor,
+        amount_renter,
+        amount_fee_earner,
+        amount_shares,
+        referrer: referrer.map(|referrer| referrer.key()),
+        amount_referrer,
+    })
+}
+
+/// Restricts purchases to allowed buyers while the presale of the exhibition is running
+pub fn check_presale(
+    exhibition: &Exhibition,
+    record: &mut BuyerRecord,
+    proof: &[[u8; 32]],
+) -> ProgramResult {
+    if let Some(presale) = &exhibition.presale {
+        let now = Clock::get()?.unix_timestamp;
+        if now < presale.start {
+            return Err(ErrorCode::SaleNotStartedError.into());
+        }
+
+        if now < presale.end {
+            if !presale.verify(&record.buyer, proof) {
+                return Err(ErrorCode::NotAllowlistedError.into());
+            }
+            if presale.max_per_wallet != 0 && record.presale_purchases >= presale.max_per_wallet {
+                return Err(ErrorCode::PurchaseCapReachedError.into());
+            }
+            record.presale_purchases = record.presale_purchases
+                .checked_add(1)
+                .ok_or(ErrorCode::MathOverflowError)?;
+        }
+    }
+
+    Ok(())
+}
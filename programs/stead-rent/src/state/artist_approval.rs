@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+
+/// Permission given by the renter to an artist to deposit pieces in a group show
+#[account]
+pub struct ArtistApproval {
+    /// The exhibition the artist can deposit in
+    pub exhibition: Pubkey,
+
+    /// The approved artist
+    pub artist: Pubkey,
+}
+
+impl ArtistApproval {
+    pub const LEN: usize = 40 + 40;
+}
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+/// Items of an exhibition sold together at a single price
+#[account]
+pub struct Bundle {
+    /// The exhibition the bundled items are a part of
+    pub exhibition: Pubkey,
+
+    /// The seller of every bundled item
+    pub seller: Pubkey,
+
+    /// The position of the bundle in the exhibition, used to derive its address
+    pub index: u64,
+
+    /// The price of the whole bundle, in lamports
+    pub price: u64,
+
+    /// The bundled items
+    pub items: Vec<Pubkey>,
+}
+
+impl Bundle {
+    pub const MIN_ITEMS: usize = 2;
+    pub const MAX_ITEMS: usize = 10;
+    pub const LEN: usize = 40 + 40 + 8 + 8 + 4 + Bundle::MAX_ITEMS * 32;
+}
@@ -0,0 +1,18 @@
+use anchor_lang::prelude::*;
+
+/// Purchases made by a wallet in an exhibition
+#[account]
+pub struct BuyerRecord {
+    /// The exhibition the purchases were made in
+    pub exhibition: Pubkey,
+
+    /// The wallet that made the purchases
+    pub buyer: Pubkey,
+
+    /// The number of pieces bought during the presale
+    pub presale_purchases: u16,
+}
+
+impl BuyerRecord {
+    pub const LEN: usize = 40 + 40 + 2;
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::*;
+
+/// Actions a renter or an exhibitor lets a manager take on their behalf
+#[account]
+pub struct Delegation {
+    /// The renter or exhibitor giving the permissions
+    pub principal: Pubkey,
+
+    /// The key allowed to act for the principal
+    pub manager: Pubkey,
+
+    /// The allowed actions, as a combination of the permission flags
+    pub permissions: u8,
+
+    /// The time after which the delegation is no longer valid
+    /// The delegation never expires if zero
+    pub expires_at: i64,
+}
+
+impl Delegation {
+    pub const DEPOSIT: u8 = 1 << 0;
+    pub const WITHDRAW: u8 = 1 << 1;
+    pub const REPRICE: u8 = 1 << 2;
+    pub const CANCEL: u8 = 1 << 3;
+    pub const ALL: u8 = Delegation::DEPOSIT | Delegation::WITHDRAW | Delegation::REPRICE | Delegation::CANCEL;
+
+    pub const LEN: usize = 40 + 40 + 1 + 8;
+
+    /// Checks that the signer is the principal, or a manager allowed to take the action
+    /// The delegation account is ignored when the principal signs
+    pub fn authorize(
+        principal: &Pubkey,
+        authority: &Pubkey,
+        delegation: &AccountInfo,
+        permission: u8,
+    ) -> ProgramResult {
+        if authority == principal {
+            return Ok(());
+        }
+
+        let delegation: Account<Delegation> = Account::try_from(delegation)
+            .map_err(|_| ErrorCode::NotDelegatedError)?;
+        if delegation.principal != *principal
+            || delegation.manager != *authority
+            || delegation.permissions & permission == 0
+        {
+            return Err(ErrorCode::NotDelegatedError.into());
+        }
+        if delegation.expires_at != 0 && Clock::get()?.unix_timestamp >= delegation.expires_at {
+            return Err(ErrorCode::DelegationExpiredError.into());
+        }
+
+        Ok(())
+    }
+}
@@ -1,13 +1,111 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
 
+use crate::errors::*;
 use crate::InitExhibitionBumpSeeds;
+use crate::state::{ArtistApproval, ItemStatus, SalesStats};
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
 pub enum ExhibitionStatus {
+    Proposed,
     Active,
     Cancelled
 }
 
+/// A portion of each sale paid to a collaborator of the exhibition
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+pub struct Share {
+    /// The wallet receiving the share, such as a curator or a co-artist
+    pub recipient: Pubkey,
+
+    /// The portion of the sale, denominated in basis points
+    pub basis_points: u16,
+}
+
+impl Share {
+    pub const LEN: usize = 40 + 2;
+}
+
+/// Early access given to allowed buyers before the public sale
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+pub struct Presale {
+    /// The root of the merkle tree of allowed buyer wallets
+    pub merkle_root: [u8; 32],
+
+    /// The time at which the presale starts
+    pub start: i64,
+
+    /// The time at which the public sale starts
+    pub end: i64,
+
+    /// The maximum number of pieces a wallet can buy during the presale
+    /// No cap is applied if zero
+    pub max_per_wallet: u16,
+}
+
+impl Presale {
+    pub const LEN: usize = 32 + 8 + 8 + 2;
+
+    /// Checks that the buyer is a leaf of the merkle tree
+    pub fn verify(&self, buyer: &Pubkey, proof: &[[u8; 32]]) -> bool {
+        verify_proof(&self.merkle_root, buyer, proof)
+    }
+}
+
+/// Checks that a wallet is a leaf of the merkle tree with the given root
+/// Pairs of nodes are sorted before being hashed
+pub fn verify_proof(merkle_root: &[u8; 32], wallet: &Pubkey, proof: &[[u8; 32]]) -> bool {
+    let mut node = hashv(&[wallet.as_ref()]).to_bytes();
+    for sibling in proof {
+        node = if node <= *sibling {
+            hashv(&[&node, sibling]).to_bytes()
+        } else {
+            hashv(&[sibling, &node]).to_bytes()
+        };
+    }
+
+    node == *merkle_root
+}
+
+/// The artists allowed to deposit pieces in the exhibition
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+pub enum DepositPolicy {
+    /// Only the exhibitor of the exhibition
+    Exhibitor,
+
+    /// Any artist, for an open call
+    Open,
+
+    /// Artists whose wallet is a leaf of the merkle tree
+    Allowlist { merkle_root: [u8; 32] },
+
+    /// Artists holding an approval created by the renter
+    Approved,
+}
+
+impl DepositPolicy {
+    pub const LEN: usize = 1 + 32;
+}
+
+/// Admission to the exhibition
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+pub struct Ticketing {
+    /// The entrance fee, in lamports
+    pub price: u64,
+
+    /// How long a ticket grants entry, in seconds
+    /// Tickets are valid until the exhibition closes if zero
+    pub validity: i64,
+
+    /// The portion of the entrance fee paid to the exhibitor
+    /// Denominated in basis points
+    pub exhibitor_fee: u16,
+}
+
+impl Ticketing {
+    pub const LEN: usize = 8 + 8 + 2;
+}
+
 /// Rental property that will serve as an art gallery
 #[account]
 pub struct Exhibition {
@@ -17,22 +115,132 @@ pub struct Exhibition {
     /// The property hosting the exhibition
     pub property: Pubkey,
 
+    /// The room of the property, used to derive the exhibition address
+    pub index: u64,
+
     /// The fee earned by the renter on each sell
     pub renter_fee: u16,
 
+    /// The additional parties paid on each sell
+    pub shares: Vec<Share>,
+
     /// The owner of tokens to be displayed in the exhibition
+    /// Left to the default key for group shows, where each item records its seller
     pub exhibitor: Pubkey,
 
+    /// The artists allowed to deposit pieces
+    pub deposit_policy: DepositPolicy,
+
     /// The number of pieces currently in the exhibition
     pub n_pieces: u64,
 
+    /// The number of pieces waiting for the approval of the renter, included in `n_pieces`
+    pub n_pending: u64,
+
+    /// Whether deposited pieces must be approved by the renter before being sold
+    pub curated: bool,
+
+    /// The number of lazy listings ever created, used to derive their address
+    pub n_lazy_listings: u64,
+
+    /// The number of bundles ever created, used to derive their address
+    pub n_bundles: u64,
+
     /// The status of the exhibition
     pub status: ExhibitionStatus,
 
+    /// How long the exhibitor has to withdraw pieces after a cancellation, in seconds
+    pub notice_period: i64,
+
+    /// The time at which the exhibition was cancelled
+    pub cancelled_at: i64,
+
+    /// The presale restricting purchases to allowed buyers, if any
+    pub presale: Option<Presale>,
+
+    /// The admission charged to visitors, if any
+    pub ticketing: Option<Ticketing>,
+
+    /// The statistics of the exhibition
+    pub stats: SalesStats,
+
     /// Bumps used to sign PDA
     pub bumps: InitExhibitionBumpSeeds,
 }
 
 impl Exhibition {
-    pub const LEN: usize = 3 * 40 + 2 + 8 + 8 + 3;
+    pub const MAX_SHARES: usize = 5;
+    /// One year
+    pub const MAX_NOTICE_PERIOD: i64 = 365 * 24 * 60 * 60;
+    pub const LEN: usize = 3 * 40
+        + 8
+        + 2
+        + 4 + Exhibition::MAX_SHARES * Share::LEN
+        + DepositPolicy::LEN
+        + 8 + 8 + 8
+        + 8 + 8
+        + 8 + 1
+        + 8
+        + 1 + Presale::LEN
+        + 1 + Ticketing::LEN
+        + SalesStats::LEN
+        + 4;
+
+    /// Checks whether the exhibition is a group show without a single exhibitor
+    pub fn is_group_show(&self) -> bool {
+        self.deposit_policy != DepositPolicy::Exhibitor
+    }
+
+    /// Checks that the artist is allowed to deposit pieces in the exhibition
+    /// The approval of the artist is expected as the first remaining account for approved policies
+    pub fn check_artist(
+        &self,
+        exhibition: &Pubkey,
+        artist: &Pubkey,
+        proof: &[[u8; 32]],
+        remaining_accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        match &self.deposit_policy {
+            DepositPolicy::Exhibitor => {
+                if self.exhibitor != *artist {
+                    return Err(ErrorCode::ArtistNotAllowedError.into());
+                }
+            }
+            DepositPolicy::Open => {}
+            DepositPolicy::Allowlist { merkle_root } => {
+                if !verify_proof(merkle_root, artist, proof) {
+                    return Err(ErrorCode::ArtistNotAllowedError.into());
+                }
+            }
+            DepositPolicy::Approved => {
+                let approval = remaining_accounts.first().ok_or(ErrorCode::ArtistNotAllowedError)?;
+                let approval: Account<ArtistApproval> = Account::try_from(approval)
+                    .map_err(|_| ErrorCode::ArtistNotAllowedError)?;
+                if approval.exhibition != *exhibition || approval.artist != *artist {
+                    return Err(ErrorCode::ArtistNotAllowedError.into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Updates the piece counters when an unsold piece leaves the exhibition
+    pub fn remove_piece(&mut self, status: &ItemStatus) -> ProgramResult {
+        self.n_pieces = self.n_pieces
+            .checked_sub(1)
+            .ok_or(ErrorCode::MathOverflowError)?;
+        if *status == ItemStatus::Pending {
+            self.n_pending = self.n_pending
+                .checked_sub(1)
+                .ok_or(ErrorCode::MathOverflowError)?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks whether remaining pieces can be returned to the exhibitor
+    pub fn notice_elapsed(&self, now: i64) -> bool {
+        self.status == ExhibitionStatus::Cancelled && now >= self.cancelled_at.saturating_add(self.notice_period)
+    }
 }
\ No newline at end of file
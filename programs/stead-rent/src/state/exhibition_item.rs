@@ -1,5 +1,57 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::*;
+
+/// Display information attached to an item by its exhibitor
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+pub struct ItemMetadata {
+    /// A short caption displayed next to the item
+    pub caption: String,
+
+    /// The wall slot where the item hangs in the stead
+    pub position: u16,
+
+    /// An optional link to external content about the item
+    pub uri: Option<String>,
+}
+
+impl ItemMetadata {
+    pub const MAX_CAPTION_LEN: usize = 64;
+    pub const MAX_URI_LEN: usize = 200;
+    pub const LEN: usize = 4 + ItemMetadata::MAX_CAPTION_LEN + 2 + 1 + 4 + ItemMetadata::MAX_URI_LEN;
+
+    /// Checks that the metadata fits in the space allocated for the item
+    pub fn validate(&self) -> ProgramResult {
+        if self.caption.len() > ItemMetadata::MAX_CAPTION_LEN {
+            return Err(ErrorCode::CaptionTooLongError.into());
+        }
+        if let Some(uri) = &self.uri {
+            if uri.len() > ItemMetadata::MAX_URI_LEN {
+                return Err(ErrorCode::UriTooLongError.into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The denomination of the price of an item
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+pub enum Currency {
+    /// The price is a fixed amount of lamports
+    Lamports,
+    /// The price is an amount of USD cents, converted to lamports when bought
+    UsdCents,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+pub enum ItemStatus {
+    /// Waiting for the renter of a curated exhibition to approve it
+    Pending,
+    /// Available for sale
+    Listed,
+}
+
 /// Rental property that will serve as an art gallery
 #[account]
 pub struct ExhibitionItem {
@@ -9,10 +61,28 @@ pub struct ExhibitionItem {
     /// The mint of the item
     pub mint: Pubkey,
 
-    /// The price defined by the exhibitor
+    /// The artist who deposited the item and is paid when it sells
+    pub seller: Pubkey,
+
+    /// The wallet that paid for the account holding the token, refunded when it is closed
+    pub payer: Pubkey,
+
+    /// The price defined by the seller
     pub price: u64,
+
+    /// The denomination of the price
+    pub currency: Currency,
+
+    /// How the item is displayed in the exhibition
+    pub metadata: ItemMetadata,
+
+    /// Whether the item can be bought
+    pub status: ItemStatus,
+
+    /// The bundle the item is sold with, if any
+    pub bundle: Option<Pubkey>,
 }
 
 impl ExhibitionItem {
-    pub const LEN: usize = 40 + 40 + 8;
-}
\ No newline at end of file
+    pub const LEN: usize = 40 + 40 + 40 + 32 + 8 + ItemMetadata::LEN + 1 + 1 + 1 + 32;
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::*;
+
+/// A piece registered by the exhibitor without a mint
+/// The token is only minted when the piece is bought
+#[account]
+pub struct LazyListing {
+    /// The exhibition this listing is a part of
+    pub exhibition: Pubkey,
+
+    /// The position of the listing in the exhibition, used to derive its address
+    pub index: u64,
+
+    /// The artist who listed the piece and is paid when it sells
+    pub seller: Pubkey,
+
+    /// The price defined by the seller
+    pub price: u64,
+
+    /// The name of the piece
+    pub name: String,
+
+    /// The symbol of the piece
+    pub symbol: String,
+
+    /// The metadata URI of the piece
+    pub uri: String,
+}
+
+impl LazyListing {
+    pub const MAX_NAME_LEN: usize = 32;
+    pub const MAX_SYMBOL_LEN: usize = 10;
+    pub const MAX_URI_LEN: usize = 200;
+    pub const LEN: usize = 40 + 8 + 40 + 8
+        + 4 + LazyListing::MAX_NAME_LEN
+        + 4 + LazyListing::MAX_SYMBOL_LEN
+        + 4 + LazyListing::MAX_URI_LEN;
+
+    /// Checks that the listing fits in the space allocated for it
+    pub fn validate(&self) -> ProgramResult {
+        if self.name.len() > LazyListing::MAX_NAME_LEN
+            || self.symbol.len() > LazyListing::MAX_SYMBOL_LEN
+        {
+            return Err(ErrorCode::NameTooLongError.into());
+        }
+        if self.uri.len() > LazyListing::MAX_URI_LEN {
+            return Err(ErrorCode::UriTooLongError.into());
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,29 @@
 mod state;
 mod exhibition;
 mod exhibition_item;
+mod buyer_record;
+mod lazy_listing;
+mod ticket;
+mod provenance;
+mod sale_receipt;
+mod stats;
+mod artist_approval;
+mod stead;
+mod delegation;
+mod bundle;
+mod proposal;
 
 pub use state::*;
 pub use exhibition::*;
-pub use exhibition_item::*;
\ No newline at end of file
+pub use exhibition_item::*;
+pub use buyer_record::*;
+pub use lazy_listing::*;
+pub use ticket::*;
+pub use provenance::*;
+pub use sale_receipt::*;
+pub use stats::*;
+pub use artist_approval::*;
+pub use stead::*;
+pub use delegation::*;
+pub use bundle::*;
+pub use proposal::*;
\ No newline at end of file
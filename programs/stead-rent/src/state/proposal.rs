@@ -0,0 +1,174 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::*;
+use crate::state::{Share, State};
+
+/// A change of the global state submitted to the admins
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+pub enum ProposalAction {
+    /// Sets the portions of sales paid to the fee earner and to referrers,
+    /// and the bounds of the fee in lamports
+    SetFees {
+        fee_amount: u16,
+        referral_fee: u16,
+        min_fee: u64,
+        max_fee: Option<u64>,
+    },
+
+    /// Sets the wallet to which fees are given
+    SetFeeEarner { fee_earner: Pubkey },
+
+    /// Sets the wallets sharing the fees
+    SetFeeRecipients { recipients: Vec<Share> },
+
+    /// Sets the price feed used to convert USD prices to lamports
+    SetPriceOracle {
+        price_oracle: Pubkey,
+        max_price_age: i64,
+        max_confidence: u16,
+    },
+
+    /// Suspends or resumes new exhibitions, deposits and sales
+    SetPaused { paused: bool },
+
+    /// Sets the collections whose tokens can be deposited
+    SetAllowedCollections { collections: Vec<Pubkey> },
+
+    /// Replaces the admins, their threshold and the timelock
+    SetAdmins {
+        admins: Vec<Pubkey>,
+        threshold: u8,
+        timelock: i64,
+    },
+}
+
+impl ProposalAction {
+    pub const LEN: usize = 1 + 4 + State::MAX_ADMINS * 32 + 1 + 8;
+
+    pub fn validate(&self) -> ProgramResult {
+        match self {
+            ProposalAction::SetFees { fee_amount, referral_fee, min_fee, max_fee } => {
+                State::check_fees(*fee_amount, *referral_fee, *min_fee, *max_fee)
+            }
+            ProposalAction::SetFeeRecipients { recipients } => {
+                State::check_fee_recipients(recipients)
+            }
+            ProposalAction::SetPriceOracle { max_price_age, max_confidence, .. } => {
+                State::check_oracle(*max_price_age, *max_confidence)
+            }
+            ProposalAction::SetAllowedCollections { collections } => {
+                State::check_allowed_collections(collections)
+            }
+            ProposalAction::SetAdmins { admins, threshold, timelock } => {
+                State::check_admins(admins, *threshold, *timelock)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Changes the state as proposed
+    pub fn apply(&self, state: &mut State) -> ProgramResult {
+        self.validate()?;
+
+        match self.clone() {
+            ProposalAction::SetFees { fee_amount, referral_fee, min_fee, max_fee } => {
+                state.fee_amount = fee_amount;
+                state.referral_fee = referral_fee;
+                state.min_fee = min_fee;
+                state.max_fee = max_fee;
+            }
+            ProposalAction::SetFeeEarner { fee_earner } => {
+                state.fee_earner = fee_earner;
+            }
+            ProposalAction::SetFeeRecipients { recipients } => {
+                state.fee_recipients = recipients;
+            }
+            ProposalAction::SetPriceOracle { price_oracle, max_price_age, max_confidence } => {
+                state.price_oracle = price_oracle;
+                state.max_price_age = max_price_age;
+                state.max_confidence = max_confidence;
+            }
+            ProposalAction::SetPaused { paused } => {
+                state.paused = paused;
+            }
+            ProposalAction::SetAllowedCollections { collections } => {
+                state.allowed_collections = collections;
+            }
+            ProposalAction::SetAdmins { admins, threshold, timelock } => {
+                state.admins = admins;
+                state.threshold = threshold;
+                state.timelock = timelock;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A change of the global state, executed once enough admins approved it
+#[account]
+pub struct Proposal {
+    /// The position of the proposal, used to derive its address
+    pub index: u64,
+
+    /// The bump used to generate this PDA
+    pub bump: u8,
+
+    /// The admin who created the proposal and paid for its account
+    pub proposer: Pubkey,
+
+    /// The proposed change
+    pub action: ProposalAction,
+
+    /// The admins who approved the proposal
+    pub approvals: Vec<Pubkey>,
+
+    /// When the proposal can execute, set once the threshold is reached
+    pub executable_at: Option<i64>,
+
+    /// When the proposal was created
+    pub created_at: i64,
+}
+
+impl Proposal {
+    pub const LEN: usize = 8 + 8 + 1 + 32 + ProposalAction::LEN
+        + 4 + State::MAX_ADMINS * 32 + 1 + 8 + 8;
+
+    /// How long a proposal waits for approvals, then for its execution after the timelock
+    pub const LIFETIME: i64 = 14 * 24 * 60 * 60;
+
+    /// When the proposal can no longer be approved or executed
+    pub fn expires_at(&self) -> i64 {
+        self.executable_at
+            .unwrap_or(self.created_at)
+            .saturating_add(Proposal::LIFETIME)
+    }
+
+    /// The number of current admins who approved the proposal
+    pub fn n_approvals(&self, state: &State) -> usize {
+        self.approvals.iter().filter(|admin| state.is_admin(admin)).count()
+    }
+
+    /// Records the approval of an admin and starts the timelock once the threshold is reached
+    pub fn approve(&mut self, admin: Pubkey, state: &State, now: i64) -> ProgramResult {
+        if now > self.expires_at() {
+            return Err(ErrorCode::ProposalExpiredError.into());
+        }
+        if self.approvals.contains(&admin) {
+            return Err(ErrorCode::AlreadyApprovedError.into());
+        }
+
+        // Approvals of former admins no longer count
+        self.approvals.retain(|approval| state.is_admin(approval));
+        self.approvals.push(admin);
+
+        if self.executable_at.is_none() && self.n_approvals(state) >= state.threshold as usize {
+            let executable_at = now
+                .checked_add(state.timelock)
+                .ok_or(ErrorCode::MathOverflowError)?;
+            self.executable_at = Some(executable_at);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+/// The history of a piece across exhibitions
+#[account]
+pub struct Provenance {
+    /// The mint of the piece
+    pub mint: Pubkey,
+
+    /// The bump used to generate this PDA
+    pub bump: u8,
+
+    /// The number of exhibitions the piece passed through
+    pub n_exhibitions: u64,
+
+    /// The number of times the piece was sold
+    pub n_sales: u64,
+}
+
+impl Provenance {
+    pub const LEN: usize = 40 + 1 + 8 + 8;
+}
+
+/// An exhibition a piece passed through
+#[account]
+pub struct ProvenanceEntry {
+    /// The mint of the piece
+    pub mint: Pubkey,
+
+    /// The exhibition the piece was shown in
+    pub exhibition: Pubkey,
+
+    /// The property hosting the exhibition
+    pub property: Pubkey,
+
+    /// The owner of the property
+    pub renter: Pubkey,
+
+    /// The artist who showed the piece
+    pub exhibitor: Pubkey,
+
+    /// The time at which the piece entered the exhibition
+    pub timestamp: i64,
+}
+
+impl ProvenanceEntry {
+    pub const LEN: usize = 5 * 40 + 8;
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+
+/// The amounts paid to each party of a sale
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct SaleSplit {
+    /// The amount paid to the exhibitor
+    pub amount_exhibitor: u64,
+
+    /// The amount paid to the renter
+    pub amount_renter: u64,
+
+    /// The amount paid to the fee earner
+    pub amount_fee_earner: u64,
+
+    /// The total amount paid to the recipients of the exhibition shares
+    pub amount_shares: u64,
+
+    /// The marketplace that referred the buyer, if any
+    pub referrer: Option<Pubkey>,
+
+    /// The amount paid to the referrer
+    pub amount_referrer: u64,
+}
+
+impl SaleSplit {
+    pub const LEN: usize = 4 * 8 + 1 + 32 + 8;
+}
+
+/// Proof that a piece was bought in an exhibition
+#[account]
+pub struct SaleReceipt {
+    /// The mint of the piece
+    pub mint: Pubkey,
+
+    /// The exhibition the piece was sold in
+    pub exhibition: Pubkey,
+
+    /// The property hosting the exhibition
+    pub property: Pubkey,
+
+    /// The wallet that bought the piece
+    pub buyer: Pubkey,
+
+    /// The artist who sold the piece
+    pub exhibitor: Pubkey,
+
+    /// The price paid by the buyer
+    pub price: u64,
+
+    /// How the price was split between the parties
+    pub split: SaleSplit,
+
+    /// The time of the sale
+    pub timestamp: i64,
+
+    /// The bundle the piece was sold in, in which case the price and split are those of
+    /// the whole bundle
+    pub bundle: Option<Pubkey>,
+}
+
+impl SaleReceipt {
+    pub const LEN: usize = 5 * 40 + 8 + SaleSplit::LEN + 8 + 1 + 32;
+}
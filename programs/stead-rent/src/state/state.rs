@@ -1,5 +1,13 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::*;
+use crate::instructions::token_metadata;
+use crate::sale::cut;
+use crate::state::{SalesStats, Share};
+
+/// The key identifying metadata accounts of the Metaplex token metadata program
+const METADATA_V1: u8 = 4;
+
 /// The global state of the program
 #[account]
 pub struct State {
@@ -12,8 +20,199 @@ pub struct State {
     /// The portion of the sale which goes to the fee earner
     /// Denominated in basis points
     pub fee_amount: u16,
+
+    /// The portion of the sale paid to the marketplace that referred the buyer
+    /// Carved out of the fee amount, denominated in basis points
+    pub referral_fee: u16,
+
+    /// The smallest fee taken on a sale, in lamports
+    pub min_fee: u64,
+
+    /// The largest fee taken on a sale, in lamports, if the fee is capped
+    pub max_fee: Option<u64>,
+
+    /// The wallets sharing the fees, weighted in basis points of the fees
+    /// Fees go to the fee earner while it is empty
+    pub fee_recipients: Vec<Share>,
+
+    /// The statistics aggregated over all exhibitions
+    pub stats: SalesStats,
+
+    /// The Pyth account giving the price of SOL in USD
+    pub price_oracle: Pubkey,
+
+    /// How old the oracle price can be, in seconds
+    pub max_price_age: i64,
+
+    /// The widest accepted confidence interval of the oracle price
+    /// Denominated in basis points of the price
+    pub max_confidence: u16,
+
+    /// The admins changing the state through proposals
+    /// Empty until the fee earner hands the state over to them
+    pub admins: Vec<Pubkey>,
+
+    /// The number of admins approving a proposal before it can execute
+    pub threshold: u8,
+
+    /// How long an approved proposal waits before it can execute, in seconds
+    pub timelock: i64,
+
+    /// The number of proposals ever created, used to derive their address
+    pub n_proposals: u64,
+
+    /// Whether new exhibitions, deposits and sales are suspended
+    pub paused: bool,
+
+    /// The collections whose tokens can be deposited, any collection being allowed when empty
+    /// A collection is the update authority of the Metaplex metadata of its tokens
+    pub allowed_collections: Vec<Pubkey>,
 }
 
 impl State {
-    pub const LEN: usize = 40 + 3;
+    pub const MAX_ADMINS: usize = 10;
+    pub const MAX_FEE_RECIPIENTS: usize = 5;
+    pub const MAX_ALLOWED_COLLECTIONS: usize = 10;
+    pub const LEN: usize = 40 + 3 + 2 + 8 + 9 + 4 + State::MAX_FEE_RECIPIENTS * Share::LEN
+        + SalesStats::LEN + 32 + 8 + 2
+        + 4 + State::MAX_ADMINS * 32 + 1 + 8 + 8 + 1
+        + 4 + State::MAX_ALLOWED_COLLECTIONS * 32;
+
+    /// Whether the state is changed through proposals rather than by the fee earner
+    pub fn is_governed(&self) -> bool {
+        !self.admins.is_empty()
+    }
+
+    pub fn is_admin(&self, key: &Pubkey) -> bool {
+        self.admins.contains(key)
+    }
+
+    /// Checks that the referral fee is carved out of a fee below the price,
+    /// and that the minimum fee does not exceed the maximum
+    pub fn check_fees(
+        fee_amount: u16,
+        referral_fee: u16,
+        min_fee: u64,
+        max_fee: Option<u64>,
+    ) -> ProgramResult {
+        if fee_amount > 10000
+            || referral_fee > fee_amount
+            || max_fee.map_or(false, |max_fee| min_fee > max_fee)
+        {
+            return Err(ErrorCode::FeeOutOfRangeError.into());
+        }
+
+        Ok(())
+    }
+
+    /// The fee taken on a sale, bounded by the minimum and maximum fees
+    /// The fee never exceeds the price
+    pub fn fee(&self, price: u64) -> u64 {
+        let fee = cut(price, self.fee_amount).max(self.min_fee);
+        let fee = match self.max_fee {
+            Some(max_fee) => fee.min(max_fee),
+            None => fee,
+        };
+        fee.min(price)
+    }
+
+    /// The error for a price that cannot pay every portion of a sale,
+    /// blaming the minimum fee only when it raised the fee
+    pub fn shortfall_error(&self, price: u64) -> ErrorCode {
+        if self.fee(price) > cut(price, self.fee_amount) {
+            ErrorCode::PriceBelowMinimumFeeError
+        } else {
+            ErrorCode::FeeExceedsPriceError
+        }
+    }
+
+    /// Checks that the fee recipients are distinct and share the whole fees
+    pub fn check_fee_recipients(recipients: &[Share]) -> ProgramResult {
+        if recipients.is_empty() {
+            return Ok(());
+        }
+
+        let distinct = recipients.iter().enumerate().all(|(i, share)| {
+            recipients[..i].iter().all(|other| other.recipient != share.recipient)
+        });
+        let total: u32 = recipients.iter().map(|share| share.basis_points as u32).sum();
+        if recipients.len() > State::MAX_FEE_RECIPIENTS
+            || !distinct
+            || recipients.iter().any(|share| share.basis_points == 0)
+            || total != 10000
+        {
+            return Err(ErrorCode::InvalidFeeRecipientsError.into());
+        }
+
+        Ok(())
+    }
+
+    /// Checks that prices have a positive maximum age and a confidence of at most 100%
+    pub fn check_oracle(max_price_age: i64, max_confidence: u16) -> ProgramResult {
+        if max_price_age <= 0 || max_confidence > 10000 {
+            return Err(ErrorCode::InvalidOracleSettingsError.into());
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the allowed collections are distinct and fit in the state
+    pub fn check_allowed_collections(collections: &[Pubkey]) -> ProgramResult {
+        let distinct = collections
+            .iter()
+            .enumerate()
+            .all(|(i, collection)| !collections[..i].contains(collection));
+        if collections.len() > State::MAX_ALLOWED_COLLECTIONS || !distinct {
+            return Err(ErrorCode::InvalidCollectionsError.into());
+        }
+
+        Ok(())
+    }
+
+    /// Checks that a token belongs to an allowed collection, given its Metaplex metadata
+    pub fn check_collection(&self, mint: &Pubkey, metadata: Option<&AccountInfo>) -> ProgramResult {
+        if self.allowed_collections.is_empty() {
+            return Ok(());
+        }
+
+        let metadata = metadata.ok_or(ErrorCode::CollectionNotAllowedError)?;
+        let (address, _) = Pubkey::find_program_address(
+            &[b"metadata", token_metadata::ID.as_ref(), mint.as_ref()],
+            &token_metadata::ID,
+        );
+        if metadata.key() != address || *metadata.owner != token_metadata::ID {
+            return Err(ErrorCode::CollectionNotAllowedError.into());
+        }
+
+        // The metadata starts with its key, then its update authority and its mint
+        let data = metadata.try_borrow_data()?;
+        if data.len() < 65 || data[0] != METADATA_V1 {
+            return Err(ErrorCode::CollectionNotAllowedError.into());
+        }
+        let update_authority = Pubkey::new(&data[1..33]);
+        if !self.allowed_collections.contains(&update_authority) {
+            return Err(ErrorCode::CollectionNotAllowedError.into());
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the admins are distinct and can reach their threshold
+    pub fn check_admins(admins: &[Pubkey], threshold: u8, timelock: i64) -> ProgramResult {
+        let distinct = admins
+            .iter()
+            .enumerate()
+            .all(|(i, admin)| !admins[..i].contains(admin));
+        if admins.is_empty()
+            || admins.len() > State::MAX_ADMINS
+            || !distinct
+            || threshold == 0
+            || threshold as usize > admins.len()
+            || timelock < 0
+        {
+            return Err(ErrorCode::InvalidAdminsError.into());
+        }
+
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::*;
+use crate::state::SaleSplit;
+
+/// Cumulative statistics of the pieces going through exhibitions
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct SalesStats {
+    /// The number of pieces ever deposited
+    pub pieces_deposited: u64,
+
+    /// The number of pieces sold
+    pub pieces_sold: u64,
+
+    /// The number of pieces withdrawn without being sold
+    pub pieces_withdrawn: u64,
+
+    /// The sum of the prices paid by buyers
+    pub volume: u64,
+
+    /// The total amount paid to renters
+    pub paid_to_renter: u64,
+
+    /// The total amount paid to exhibitors
+    pub paid_to_exhibitor: u64,
+
+    /// The total amount paid to the DAO
+    pub paid_to_dao: u64,
+}
+
+impl SalesStats {
+    pub const LEN: usize = 7 * 8;
+
+    pub fn record_deposit(&mut self) -> ProgramResult {
+        add(&mut self.pieces_deposited, 1)
+    }
+
+    pub fn record_withdrawal(&mut self) -> ProgramResult {
+        add(&mut self.pieces_withdrawn, 1)
+    }
+
+    pub fn record_sale(&mut self, price: u64, split: &SaleSplit) -> ProgramResult {
+        self.record_bundle_sale(1, price, split)
+    }
+
+    /// Records the sale of several pieces at a single price
+    pub fn record_bundle_sale(
+        &mut self,
+        n_pieces: u64,
+        price: u64,
+        split: &SaleSplit,
+    ) -> ProgramResult {
+        if n_pieces == 0 {
+            return Err(ErrorCode::InvalidBundleError.into());
+        }
+
+        add(&mut self.pieces_sold, n_pieces)?;
+        add(&mut self.volume, price)?;
+        add(&mut self.paid_to_renter, split.amount_renter)?;
+        add(&mut self.paid_to_exhibitor, split.amount_exhibitor)?;
+        add(&mut self.paid_to_dao, split.amount_fee_earner)
+    }
+}
+
+/// Adds an amount to a counter, failing instead of wrapping around
+fn add(total: &mut u64, amount: u64) -> ProgramResult {
+    *total = total
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflowError)?;
+
+    Ok(())
+}
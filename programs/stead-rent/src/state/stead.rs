@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+/// Registry of the exhibitions, or rooms, hosted by a property
+#[account]
+pub struct Stead {
+    /// The property hosting the rooms
+    pub property: Pubkey,
+
+    /// The wallet that escrowed the property, the only one able to open rooms
+    pub renter: Pubkey,
+
+    /// The wallet that paid for the account holding the property, refunded when it is closed
+    pub payer: Pubkey,
+
+    /// The maximum number of rooms open at the same time
+    pub max_rooms: u16,
+
+    /// The number of rooms not closed yet
+    /// The property is given back to the renter once it drops to zero
+    pub n_open_rooms: u16,
+
+    /// The number of rooms ever opened, used to derive their address
+    pub n_rooms: u64,
+
+    /// Bump used to derive the registry address
+    pub bump: u8,
+}
+
+impl Stead {
+    pub const LEN: usize = 40 + 40 + 32 + 2 + 2 + 8 + 1;
+}
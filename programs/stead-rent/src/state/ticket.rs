@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+/// Entry of a visitor to an exhibition
+#[account]
+pub struct Ticket {
+    /// The exhibition the ticket grants entry to
+    pub exhibition: Pubkey,
+
+    /// The wallet allowed to enter
+    pub visitor: Pubkey,
+
+    /// The time after which the ticket is no longer valid
+    /// The ticket never expires if zero
+    pub expires_at: i64,
+}
+
+impl Ticket {
+    pub const LEN: usize = 40 + 40 + 8;
+}
@@ -1,5 +1,6 @@
 import assert from "assert";
-import { web3 } from "@project-serum/anchor";
+import { createHash } from "crypto";
+import { Program, web3 } from "@project-serum/anchor";
 
 import {
   TOKEN_PROGRAM_ID,
@@ -23,6 +24,93 @@ export const findAssociatedAddress = async (
 export const assertFail = async (pendingTx: Promise<any>) => {
   try {
     await pendingTx;
-    assert(false);
-  } catch (err) {}
+  } catch (err) {
+    return;
+  }
+  assert.fail("Expected the transaction to fail");
+};
+
+// Errors raised by the Anchor framework itself rather than by the program
+const frameworkErrors = {
+  ConstraintSigner: 142,
+  ConstraintSeeds: 146,
+  AccountDidNotDeserialize: 163,
+  AccountNotProgramOwned: 167,
+  AccountNotSigner: 170,
+};
+
+const errorCode = (err: any) => {
+  if (err.code !== undefined) {
+    return err.code;
+  }
+  // Transactions sent without the program client are not translated
+  const match = err.toString().match(/custom program error: (0x[0-9a-f]+)/);
+  return match ? parseInt(match[1], 16) : undefined;
+};
+
+export const assertError = async (
+  pendingTx: Promise<any>,
+  program: Program<any>,
+  name: string
+) => {
+  const code =
+    frameworkErrors[name] ??
+    program.idl.errors.find((error) => error.name === name).code;
+  try {
+    await pendingTx;
+  } catch (err) {
+    assert.strictEqual(errorCode(err), code);
+    return;
+  }
+  assert.fail(`Expected the transaction to fail with ${name}`);
+};
+
+// Anchor marks every signer of the IDL as such, this lets a test omit one
+export const withoutSigner = (
+  instruction: web3.TransactionInstruction,
+  key: web3.PublicKey
+) => {
+  for (const meta of instruction.keys) {
+    if (meta.pubkey.equals(key)) {
+      meta.isSigner = false;
+    }
+  }
+  return new web3.Transaction().add(instruction);
+};
+
+const sha256 = (...buffers: Buffer[]) =>
+  createHash("sha256").update(Buffer.concat(buffers)).digest();
+
+const hashPair = (a: Buffer, b: Buffer) =>
+  Buffer.compare(a, b) <= 0 ? sha256(a, b) : sha256(b, a);
+
+export const buildMerkleTree = (wallets: web3.PublicKey[]) => {
+  const layers: Buffer[][] = [
+    wallets.map((wallet) => sha256(wallet.toBuffer())),
+  ];
+  while (layers[layers.length - 1].length > 1) {
+    const layer = layers[layers.length - 1];
+    const next: Buffer[] = [];
+    for (let i = 0; i < layer.length; i += 2) {
+      next.push(
+        i + 1 < layer.length ? hashPair(layer[i], layer[i + 1]) : layer[i]
+      );
+    }
+    layers.push(next);
+  }
+
+  const getProof = (wallet: web3.PublicKey) => {
+    let index = wallets.findIndex((w) => w.equals(wallet));
+    const proof: number[][] = [];
+    for (const layer of layers.slice(0, -1)) {
+      const sibling = index ^ 1;
+      if (sibling < layer.length) {
+        proof.push([...layer[sibling]]);
+      }
+      index >>= 1;
+    }
+    return proof;
+  };
+
+  return { root: [...layers[layers.length - 1][0]], getProof };
 };
@@ -12,13 +12,20 @@ import {
   Keypair,
   SystemProgram,
   SYSVAR_RENT_PUBKEY,
+  Transaction,
 } from "@solana/web3.js";
 import {
   TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
   Token
 } from "@solana/spl-token";
 import { SteadRent } from "../target/types/stead_rent";
-import { assertFail } from "./helpers";
+import {
+  assertError,
+  buildMerkleTree,
+  findAssociatedAddress,
+  withoutSigner,
+} from "./helpers";
 
 describe("stead-rent", () => {
   const provider = Provider.local();
@@ -31,22 +38,47 @@ describe("stead-rent", () => {
   const renter = Keypair.generate();
   const exhibitor = Keypair.generate();
   const buyer = Keypair.generate();
+  const curator = Keypair.generate();
+  const marketplace = Keypair.generate();
+  const depositPayer = Keypair.generate();
   let state: any;
+  let leftBundle: any;
 
-  const collectionSize = 3;
+  const collectionSize = 5;
   const initialBalance = new BN(10 ** 10);
   const feeAmount = 250;
+  const referralFee = 100;
   const renterFee = 250;
+  const noticePeriod = 0;
+  const curatorFee = 500;
 
   const mintKeys: Token[] = Array(collectionSize).fill(undefined);
   const tokenAccounts: PublicKey[] = Array(collectionSize).fill(undefined);
 
   const indexRented = 0;
   const indexDeposited = 1;
+  const indexReturned = 3;
+  const indexProposed = 4;
+
+  const tokenMetadataProgramId = new PublicKey(
+    "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+  );
+
+  const allowlist = buildMerkleTree([buyer.publicKey, marketplace.publicKey]);
+
+  const lamports = { lamports: {} };
+  const usdCents = { usdCents: {} };
+
+  // Local price account, see Anchor.toml
+  const priceOracle = new PublicKey(
+    "H6ARHf6YXhGYeQfUzQNGk6rDNnLBQKrenN712K4AQJEG"
+  );
+  const maxPriceAge = 10 * 365 * 24 * 3600;
+  const maxConfidence = 100;
 
   it("Mints NFTs", async () => {
     await Promise.all(
-      [renter, exhibitor, buyer].map(
+      [renter, exhibitor, buyer, depositPayer].map(
         (keypair) =>
           new Promise(async (resolve) => {
             const airdrop = await provider.connection.requestAirdrop(
@@ -63,7 +95,9 @@ describe("stead-rent", () => {
     for (let i = 0; i < collectionSize; i++) {
       promises.push(
         new Promise(async (resolve) => {
-          const keypair = i === indexRented ? renter : exhibitor;
+          const keypair = [indexRented, indexProposed].includes(i)
+            ? renter
+            : exhibitor;
 
           mintKeys[i] = await Token.createMint(
             provider.connection,
@@ -96,53 +130,182 @@ describe("stead-rent", () => {
     );
     state = stateAddress;
 
-    await program.rpc.initializeState(stateBump, dao.publicKey, feeAmount, {
-      accounts: {
-        state: stateAddress,
-        payer: provider.wallet.publicKey,
-        rent: SYSVAR_RENT_PUBKEY,
-        systemProgram: SystemProgram.programId,
-      },
-    });
+    await program.rpc.initializeState(
+      stateBump,
+      dao.publicKey,
+      feeAmount,
+      referralFee,
+      new BN(0),
+      null,
+      {
+        accounts: {
+          state: stateAddress,
+          payer: provider.wallet.publicKey,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+      }
+    );
 
     const s = await program.account.state.fetch(state);
 
     expect(s.feeEarner.toString()).to.equal(dao.publicKey.toString());
     expect(s.feeAmount).to.equal(feeAmount);
+    expect(s.referralFee).to.equal(referralFee);
+    expect(s.minFee.toNumber()).to.equal(0);
+    expect(s.maxFee).to.be.null;
   });
 
   it("Sets state", async () => {
-    await program.rpc.setState(otherDao.publicKey, feeAmount * 2, {
-      accounts: {
-        state: state,
-        owner: dao.publicKey,
-      },
-      signers: [dao],
-    });
+    await program.rpc.setState(
+      otherDao.publicKey,
+      feeAmount * 2,
+      0,
+      new BN(1000),
+      new BN(10 ** 9),
+      true,
+      {
+        accounts: {
+          state: state,
+          owner: dao.publicKey,
+        },
+        signers: [dao],
+      }
+    );
 
     let s = await program.account.state.fetch(state);
     expect(s.feeEarner.toString()).to.equal(otherDao.publicKey.toString());
     expect(s.feeAmount).to.equal(feeAmount * 2);
+    expect(s.referralFee).to.equal(0);
+    expect(s.minFee.toNumber()).to.equal(1000);
+    expect(s.maxFee.toNumber()).to.equal(10 ** 9);
+    // The fee earner pauses the program until it hands it over to admins
+    expect(s.paused).to.be.true;
+
+    // The referral fee is carved out of the fee amount
+    await assertError(
+      program.rpc.setState(
+        dao.publicKey,
+        feeAmount,
+        feeAmount + 1,
+        new BN(0),
+        null,
+        false,
+        {
+          accounts: {
+            state: state,
+            owner: otherDao.publicKey,
+          },
+          signers: [otherDao],
+        }
+      ),
+      program,
+      "FeeOutOfRangeError"
+    );
+
+    // The minimum fee cannot exceed the maximum
+    await assertError(
+      program.rpc.setState(
+        dao.publicKey,
+        feeAmount,
+        referralFee,
+        new BN(1001),
+        new BN(1000),
+        false,
+        {
+          accounts: {
+            state: state,
+            owner: otherDao.publicKey,
+          },
+          signers: [otherDao],
+        }
+      ),
+      program,
+      "FeeOutOfRangeError"
+    );
+
+    await program.rpc.setState(
+      dao.publicKey,
+      feeAmount,
+      referralFee,
+      new BN(0),
+      null,
+      false,
+      {
+        accounts: {
+          state: state,
+          owner: otherDao.publicKey,
+        },
+        signers: [otherDao],
+      }
+    );
+
+    s = await program.account.state.fetch(state);
+    expect(s.feeEarner.toString()).to.equal(dao.publicKey.toString());
+    expect(s.feeAmount).to.equal(feeAmount);
+    expect(s.referralFee).to.equal(referralFee);
+    expect(s.minFee.toNumber()).to.equal(0);
+    expect(s.maxFee).to.be.null;
+    expect(s.paused).to.be.false;
+  });
+
+  it("Sets the price oracle", async () => {
+    // Only the fee earner sets the oracle
+    await assertError(
+      program.rpc.setPriceOracle(priceOracle, new BN(maxPriceAge), maxConfidence, {
+        accounts: {
+          state: state,
+          owner: otherDao.publicKey,
+        },
+        signers: [otherDao],
+      }),
+      program,
+      "NotFeeEarnerError"
+    );
 
-    await program.rpc.setState(dao.publicKey, feeAmount, {
+    // Prices must expire and cannot be wider than themselves
+    for (const [age, confidence] of [
+      [0, maxConfidence],
+      [maxPriceAge, 10001],
+    ]) {
+      await assertError(
+        program.rpc.setPriceOracle(priceOracle, new BN(age), confidence, {
+          accounts: {
+            state: state,
+            owner: dao.publicKey,
+          },
+          signers: [dao],
+        }),
+        program,
+        "InvalidOracleSettingsError"
+      );
+    }
+
+    await program.rpc.setPriceOracle(priceOracle, new BN(maxPriceAge), maxConfidence, {
       accounts: {
         state: state,
-        owner: otherDao.publicKey,
+        owner: dao.publicKey,
       },
-      signers: [otherDao],
+      signers: [dao],
     });
 
-    s = await program.account.state.fetch(state);
-    expect(s.feeEarner.toString()).to.equal(dao.publicKey.toString());
-    expect(s.feeAmount).to.equal(feeAmount);
+    const s = await program.account.state.fetch(state);
+    expect(s.priceOracle.toString()).to.equal(priceOracle.toString());
+    expect(s.maxPriceAge.toNumber()).to.equal(maxPriceAge);
+    expect(s.maxConfidence).to.equal(maxConfidence);
   });
 
   it("Creates a new exhibition", async () => {
+    const [stead, steadBump] = await web3.PublicKey.findProgramAddress(
+      [Buffer.from("stead", "utf8"), mintKeys[indexRented].publicKey.toBuffer()],
+      program.programId
+    );
     const [exhibition, exhibitionBump] =
       await web3.PublicKey.findProgramAddress(
         [
           Buffer.from("exhibition", "utf8"),
           mintKeys[indexRented].publicKey.toBuffer(),
+          new BN(0).toArrayLike(Buffer, "le", 8),
         ],
         program.programId
       );
@@ -163,28 +326,73 @@ describe("stead-rent", () => {
       );
 
     const bumps = {
+      stead: steadBump,
       exhibition: exhibitionBump,
       escrow: escrowBump,
       exhibitionToken: exhibitionTokenBump,
     };
 
-    await program.rpc.initializeExhibition(bumps, renterFee, {
-      accounts: {
-        state: state,
-        exhibition: exhibition,
-        escrow: escrow,
-        exhibitionTokenMint: mintKeys[indexRented].publicKey,
-        exhibitionTokenAccount: exhibitionTokenKey,
-        renter: renter.publicKey,
-        renterAccount: tokenAccounts[indexRented],
-        exhibitor: exhibitor.publicKey,
-        payer: provider.wallet.publicKey,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        rent: SYSVAR_RENT_PUBKEY,
-        systemProgram: SystemProgram.programId,
-      },
-      signers: [renter],
-    });
+    const accounts = {
+      state: state,
+      stead: stead,
+      exhibition: exhibition,
+      escrow: escrow,
+      exhibitionTokenMint: mintKeys[indexRented].publicKey,
+      exhibitionTokenAccount: exhibitionTokenKey,
+      renter: renter.publicKey,
+      renterAccount: tokenAccounts[indexRented],
+      exhibitor: exhibitor.publicKey,
+      payer: provider.wallet.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      rent: SYSVAR_RENT_PUBKEY,
+      systemProgram: SystemProgram.programId,
+    };
+
+    // Shares cannot exceed what is left after the renter and DAO fees
+    await assertError(
+      program.rpc.initializeExhibition(
+        bumps,
+        renterFee,
+        [{ recipient: curator.publicKey, basisPoints: 10000 - renterFee }],
+        new BN(noticePeriod),
+        { exhibitor: {} },
+        false,
+        { accounts, signers: [renter] }
+      ),
+      program,
+      "FeeOutOfRangeError"
+    );
+
+    // The payer must sign for the accounts it funds
+    await assertError(
+      provider.send(
+        withoutSigner(
+          program.instruction.initializeExhibition(
+            bumps,
+            renterFee,
+            [{ recipient: curator.publicKey, basisPoints: curatorFee }],
+            new BN(noticePeriod),
+            { exhibitor: {} },
+            false,
+            { accounts: { ...accounts, payer: buyer.publicKey } }
+          ),
+          buyer.publicKey
+        ),
+        [renter]
+      ),
+      program,
+      "AccountNotSigner"
+    );
+
+    await program.rpc.initializeExhibition(
+      bumps,
+      renterFee,
+      [{ recipient: curator.publicKey, basisPoints: curatorFee }],
+      new BN(noticePeriod),
+      { exhibitor: {} },
+      false,
+      { accounts, signers: [renter] }
+    );
 
     const exhibitionToken = new Token(
       provider.connection,
@@ -201,6 +409,214 @@ describe("stead-rent", () => {
 
     expect(renterAccount.amount.toNumber()).to.equal(new BN(0).toNumber());
     expect(escrowAccount.amount.toNumber()).to.equal(new BN(1).toNumber());
+
+    const { status } = await program.account.exhibition.fetch(exhibition);
+    expect("proposed" in status).to.be.true;
+  });
+
+  it("Accepts the exhibition", async () => {
+    const [exhibition] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("exhibition", "utf8"),
+        mintKeys[indexRented].publicKey.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+
+    // Only the exhibitor can accept the terms
+    await assertError(
+      program.rpc.acceptExhibition({
+        accounts: {
+          exhibition: exhibition,
+          exhibitor: renter.publicKey,
+        },
+        signers: [renter],
+      }),
+      program,
+      "NotExhibitorError"
+    );
+
+    await program.rpc.acceptExhibition({
+      accounts: {
+        exhibition: exhibition,
+        exhibitor: exhibitor.publicKey,
+      },
+      signers: [exhibitor],
+    });
+
+    const { status } = await program.account.exhibition.fetch(exhibition);
+    expect("active" in status).to.be.true;
+  });
+
+  it("Opens several rooms", async () => {
+    const [stead, steadBump] = await web3.PublicKey.findProgramAddress(
+      [Buffer.from("stead", "utf8"), mintKeys[indexRented].publicKey.toBuffer()],
+      program.programId
+    );
+    const [room, roomBump] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("exhibition", "utf8"),
+        mintKeys[indexRented].publicKey.toBuffer(),
+        new BN(1).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const [escrow, escrowBump] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("escrow", "utf8"),
+        mintKeys[indexRented].publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const [exhibitionTokenKey, exhibitionTokenBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("token_account", "utf8"),
+          mintKeys[indexRented].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+    const openRoom = (
+      wallet = renter,
+      walletAccount = tokenAccounts[indexRented]
+    ) =>
+      program.rpc.initializeExhibition(
+        {
+          stead: steadBump,
+          exhibition: roomBump,
+          escrow: escrowBump,
+          exhibitionToken: exhibitionTokenBump,
+        },
+        renterFee,
+        [],
+        new BN(noticePeriod),
+        { exhibitor: {} },
+        false,
+        {
+          accounts: {
+            state: state,
+            stead: stead,
+            exhibition: room,
+            escrow: escrow,
+            exhibitionTokenMint: mintKeys[indexRented].publicKey,
+            exhibitionTokenAccount: exhibitionTokenKey,
+            renter: wallet.publicKey,
+            renterAccount: walletAccount,
+            exhibitor: exhibitor.publicKey,
+            payer: provider.wallet.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [wallet],
+        }
+      );
+
+    // Only the renter of the property opens more rooms in it
+    const buyerAccount = await mintKeys[indexRented].createAccount(
+      buyer.publicKey
+    );
+    await assertError(
+      openRoom(buyer, buyerAccount),
+      program,
+      "SteadRenterMismatchError"
+    );
+
+    // A stead hosts a single room by default
+    await assertError(openRoom(), program, "RoomLimitReachedError");
+
+    await program.rpc.setRoomLimit(2, {
+      accounts: {
+        stead: stead,
+        renter: renter.publicKey,
+      },
+      signers: [renter],
+    });
+
+    await openRoom();
+
+    let registry = await program.account.stead.fetch(stead);
+    expect(registry.nOpenRooms).to.equal(2);
+    expect(registry.nRooms.toNumber()).to.equal(2);
+
+    // The renter hands the new room to another artist
+    const replaceAccounts = {
+      state: state,
+      exhibition: room,
+      renter: renter.publicKey,
+      exhibitor: curator.publicKey,
+    };
+    await assertError(
+      program.rpc.replaceExhibitor(renterFee * 2, {
+        accounts: { ...replaceAccounts, renter: exhibitor.publicKey },
+        signers: [exhibitor],
+      }),
+      program,
+      "NotRenterError"
+    );
+    let replacedEvent: any;
+    const listener = program.addEventListener(
+      "ExhibitorReplaced",
+      (event) => (replacedEvent = event)
+    );
+    await program.rpc.replaceExhibitor(renterFee * 2, {
+      accounts: replaceAccounts,
+      signers: [renter],
+    });
+    // Events arrive with the transaction logs
+    for (let i = 0; i < 20 && !replacedEvent; i++) {
+      await new Promise((resolve) => setTimeout(resolve, 100));
+    }
+    await program.removeEventListener(listener);
+
+    expect(replacedEvent.exhibition.toString()).to.equal(room.toString());
+    expect(replacedEvent.previousExhibitor.toString()).to.equal(
+      exhibitor.publicKey.toString()
+    );
+    expect(replacedEvent.exhibitor.toString()).to.equal(
+      curator.publicKey.toString()
+    );
+    expect(replacedEvent.renterFee).to.equal(renterFee * 2);
+
+    const replaced = await program.account.exhibition.fetch(room);
+    expect(replaced.exhibitor.toString()).to.equal(
+      curator.publicKey.toString()
+    );
+    expect(replaced.renterFee).to.equal(renterFee * 2);
+    expect("proposed" in replaced.status).to.be.true;
+
+    await program.rpc.withdrawProposal(
+      { exhibitionToken: exhibitionTokenBump },
+      {
+        accounts: {
+          stead: stead,
+          exhibition: room,
+          authority: renter.publicKey,
+          renter: renter.publicKey,
+          renterAccount: tokenAccounts[indexRented],
+          escrow: escrow,
+          exhibitionTokenAccount: exhibitionTokenKey,
+          depositPayer: provider.wallet.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+        signers: [renter],
+      }
+    );
+
+    // The stead stays in escrow for the first room
+    const steadToken = new Token(
+      provider.connection,
+      mintKeys[indexRented].publicKey,
+      TOKEN_PROGRAM_ID,
+      renter
+    );
+    const escrowAccount = await steadToken.getAccountInfo(exhibitionTokenKey);
+    expect(escrowAccount.amount.toNumber()).to.equal(1);
+
+    registry = await program.account.stead.fetch(stead);
+    expect(registry.nOpenRooms).to.equal(1);
   });
 
   it("Deposits an item", async () => {
@@ -208,6 +624,7 @@ describe("stead-rent", () => {
       [
         Buffer.from("exhibition", "utf8"),
         mintKeys[indexRented].publicKey.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
       ],
       program.programId
     );
@@ -236,28 +653,126 @@ describe("stead-rent", () => {
         program.programId
       );
 
+    const [provenance, provenanceBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("provenance", "utf8"),
+          mintKeys[indexDeposited].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [provenanceEntry, provenanceEntryBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("provenance", "utf8"),
+          mintKeys[indexDeposited].publicKey.toBuffer(),
+          new BN(0).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+
     const bumps = {
       item: exhibitionItemBump,
       tokenAccount: depositedTokenBump,
+      provenance: provenanceBump,
+      provenanceEntry: provenanceEntryBump,
     };
 
     const definedPrice = new BN(10 ** 9);
+    const metadata = {
+      caption: "Sunset over the stead",
+      position: 3,
+      uri: "https://example.com/sunset",
+    };
 
-    await program.rpc.depositToken(bumps, definedPrice, {
-      accounts: {
-        exhibition: exhibition,
-        exhibitionItem: exhibitionItemKey,
-        escrow: escrow,
-        depositedTokenMint: mintKeys[indexDeposited].publicKey,
-        depositedTokenAccount: depositedTokenKey,
-        exhibitor: exhibitor.publicKey,
-        exhibitorAccount: tokenAccounts[indexDeposited],
-        payer: provider.wallet.publicKey,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        rent: SYSVAR_RENT_PUBKEY,
-        systemProgram: SystemProgram.programId,
-      },
-      signers: [exhibitor],
+    const accounts = {
+      state: state,
+      exhibition: exhibition,
+      exhibitionItem: exhibitionItemKey,
+      escrow: escrow,
+      depositedTokenMint: mintKeys[indexDeposited].publicKey,
+      depositedTokenAccount: depositedTokenKey,
+      exhibitor: exhibitor.publicKey,
+      authority: exhibitor.publicKey,
+      delegation: exhibitor.publicKey,
+      provenance: provenance,
+      provenanceEntry: provenanceEntry,
+      exhibitorAccount: tokenAccounts[indexDeposited],
+      payer: depositPayer.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      rent: SYSVAR_RENT_PUBKEY,
+      systemProgram: SystemProgram.programId,
+    };
+
+    // The payer must sign the deposit
+    await assertError(
+      provider.send(
+        withoutSigner(
+          program.instruction.depositToken(
+            bumps,
+            definedPrice,
+            lamports,
+            metadata,
+            [],
+            { accounts: { ...accounts, payer: renter.publicKey } }
+          ),
+          renter.publicKey
+        ),
+        [exhibitor]
+      ),
+      program,
+      "AccountNotSigner"
+    );
+
+    // The deposited mint must be a real mint
+    await assertError(
+      program.rpc.depositToken(bumps, definedPrice, lamports, metadata, [], {
+        accounts: { ...accounts, depositedTokenMint: renter.publicKey },
+        signers: [exhibitor, depositPayer],
+      }),
+      program,
+      "AccountNotProgramOwned"
+    );
+
+    // Tokens of other collections are refused once collections are restricted
+    const setAllowedCollections = (collections: PublicKey[]) =>
+      program.rpc.setAllowedCollections(collections, {
+        accounts: { state: state, owner: dao.publicKey },
+        signers: [dao],
+      });
+    await assertError(
+      setAllowedCollections([dao.publicKey, dao.publicKey]),
+      program,
+      "InvalidCollectionsError"
+    );
+    await setAllowedCollections([dao.publicKey]);
+    const [tokenMetadata] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("metadata", "utf8"),
+        tokenMetadataProgramId.toBuffer(),
+        mintKeys[indexDeposited].publicKey.toBuffer(),
+      ],
+      tokenMetadataProgramId
+    );
+    for (const remainingAccounts of [
+      [],
+      [{ pubkey: tokenMetadata, isWritable: false, isSigner: false }],
+    ]) {
+      await assertError(
+        program.rpc.depositToken(bumps, definedPrice, lamports, metadata, [], {
+          accounts,
+          remainingAccounts,
+          signers: [exhibitor, depositPayer],
+        }),
+        program,
+        "CollectionNotAllowedError"
+      );
+    }
+    await setAllowedCollections([]);
+
+    await program.rpc.depositToken(bumps, definedPrice, lamports, metadata, [], {
+      accounts,
+      signers: [exhibitor, depositPayer],
     });
 
     const depositedToken = new Token(
@@ -282,7 +797,88 @@ describe("stead-rent", () => {
     expect(exhibitionItem.exhibition.toString()).to.equal(
       exhibition.toString()
     );
+    expect(exhibitionItem.seller.toString()).to.equal(
+      exhibitor.publicKey.toString()
+    );
     expect(exhibitionItem.price.toNumber()).to.equal(definedPrice.toNumber());
+    expect(exhibitionItem.metadata.caption).to.equal(metadata.caption);
+    expect(exhibitionItem.metadata.position).to.equal(metadata.position);
+    expect(exhibitionItem.metadata.uri).to.equal(metadata.uri);
+
+    const { nExhibitions } = await program.account.provenance.fetch(provenance);
+    expect(nExhibitions.toNumber()).to.equal(1);
+
+    const entry = await program.account.provenanceEntry.fetch(provenanceEntry);
+    expect(entry.exhibition.toString()).to.equal(exhibition.toString());
+    expect(entry.renter.toString()).to.equal(renter.publicKey.toString());
+
+    // The exhibitor cannot be replaced while pieces are exhibited
+    await assertError(
+      program.rpc.replaceExhibitor(null, {
+        accounts: {
+          state: state,
+          exhibition: exhibition,
+          renter: renter.publicKey,
+          exhibitor: curator.publicKey,
+        },
+        signers: [renter],
+      }),
+      program,
+      "ExhibitionNotEmptyError"
+    );
+  });
+
+  it("Edits item metadata", async () => {
+    const [exhibition] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("exhibition", "utf8"),
+        mintKeys[indexRented].publicKey.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const [exhibitionItemKey] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("item", "utf8"),
+        exhibition.toBuffer(),
+        mintKeys[indexDeposited].publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+
+    const metadata = { caption: "Moved to the hall", position: 7, uri: null };
+
+    await program.rpc.setItemMetadata(metadata, {
+      accounts: {
+        exhibition: exhibition,
+        exhibitionItem: exhibitionItemKey,
+        exhibitor: exhibitor.publicKey,
+      },
+      signers: [exhibitor],
+    });
+
+    const exhibitionItem = await program.account.exhibitionItem.fetch(
+      exhibitionItemKey
+    );
+    expect(exhibitionItem.metadata.caption).to.equal(metadata.caption);
+    expect(exhibitionItem.metadata.position).to.equal(metadata.position);
+    expect(exhibitionItem.metadata.uri).to.be.null;
+
+    await assertError(
+      program.rpc.setItemMetadata(
+        { caption: "x".repeat(65), position: 0, uri: null },
+        {
+          accounts: {
+            exhibition: exhibition,
+            exhibitionItem: exhibitionItemKey,
+            exhibitor: exhibitor.publicKey,
+          },
+          signers: [exhibitor],
+        }
+      ),
+      program,
+      "CaptionTooLongError"
+    );
   });
 
   it("Withdraw an item", async () => {
@@ -292,6 +888,7 @@ describe("stead-rent", () => {
       [
         Buffer.from("exhibition", "utf8"),
         mintKeys[indexRented].publicKey.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
       ],
       program.programId
     );
@@ -320,45 +917,91 @@ describe("stead-rent", () => {
         program.programId
       );
 
+    const [provenance, provenanceBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("provenance", "utf8"),
+          mintKeys[indexOtherDeposit].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [provenanceEntry, provenanceEntryBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("provenance", "utf8"),
+          mintKeys[indexOtherDeposit].publicKey.toBuffer(),
+          new BN(0).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+
     const bumps = {
       item: exhibitionItemBump,
       tokenAccount: depositedTokenBump,
+      provenance: provenanceBump,
+      provenanceEntry: provenanceEntryBump,
     };
 
     const definedPrice = new BN(10 ** 9);
+    const metadata = { caption: "", position: 0, uri: null };
 
-    await program.rpc.depositToken(bumps, definedPrice, {
+    await program.rpc.depositToken(bumps, definedPrice, lamports, metadata, [], {
       accounts: {
+        state: state,
         exhibition: exhibition,
         exhibitionItem: exhibitionItemKey,
         escrow: escrow,
         depositedTokenMint: mintKeys[indexOtherDeposit].publicKey,
         depositedTokenAccount: depositedTokenKey,
         exhibitor: exhibitor.publicKey,
+        authority: exhibitor.publicKey,
+        delegation: exhibitor.publicKey,
+        provenance: provenance,
+        provenanceEntry: provenanceEntry,
         exhibitorAccount: tokenAccounts[indexOtherDeposit],
-        payer: provider.wallet.publicKey,
+        payer: depositPayer.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
         rent: SYSVAR_RENT_PUBKEY,
         systemProgram: SystemProgram.programId,
       },
-      signers: [exhibitor],
+      signers: [exhibitor, depositPayer],
     });
 
     const withdrawBumps = {
       tokenAccount: depositedTokenBump,
     }
 
+    const withdrawAccounts = {
+      state: state,
+      exhibition: exhibition,
+      exhibitor: exhibitor.publicKey,
+      authority: exhibitor.publicKey,
+      delegation: exhibitor.publicKey,
+      exhibitorAccount: tokenAccounts[indexOtherDeposit],
+      exhibitionItem: exhibitionItemKey,
+      escrow: escrow,
+      depositedTokenMint: mintKeys[indexOtherDeposit].publicKey,
+      depositedTokenAccount: depositedTokenKey,
+      depositPayer: depositPayer.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    // The rent of the token account only goes back to the wallet that paid for it
+    await assertError(
+      program.rpc.withdrawToken(bumps, {
+        accounts: { ...withdrawAccounts, depositPayer: exhibitor.publicKey },
+        signers: [exhibitor],
+      }),
+      program,
+      "NotDepositPayerError"
+    );
+
+    const balancePayerBefore = await provider.connection.getBalance(
+      depositPayer.publicKey
+    );
+
     await program.rpc.withdrawToken(bumps, {
-      accounts: {
-        exhibition: exhibition,
-        exhibitor: exhibitor.publicKey,
-        exhibitorAccount: tokenAccounts[indexOtherDeposit],
-        exhibitionItem: exhibitionItemKey,
-        escrow: escrow,
-        depositedTokenMint: mintKeys[indexOtherDeposit].publicKey,
-        depositedTokenAccount: depositedTokenKey,
-        tokenProgram: TOKEN_PROGRAM_ID,
-      },
+      accounts: withdrawAccounts,
       signers: [exhibitor],
     });
 
@@ -371,19 +1014,73 @@ describe("stead-rent", () => {
     const exhibitorAccount = await depositedToken.getAccountInfo(
       tokenAccounts[indexOtherDeposit]
     );
-    const escrowAccount = await depositedToken.getAccountInfo(
-      depositedTokenKey
-    );
-
     expect(exhibitorAccount.amount.toNumber()).to.equal(new BN(1).toNumber());
-    expect(escrowAccount.amount.toNumber()).to.equal(new BN(0).toNumber());
-  });
 
-  it("Buys an item", async () => {
+    // The emptied escrow account is closed and its rent refunded
+    expect(
+      await provider.connection.getAccountInfo(depositedTokenKey)
+    ).to.equal(null);
+    expect(
+      await provider.connection.getBalance(depositPayer.publicKey)
+    ).to.be.above(balancePayerBefore);
+  });
+
+  it("Sets a presale", async () => {
+    const [exhibition] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("exhibition", "utf8"),
+        mintKeys[indexRented].publicKey.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+
+    const now = Math.floor(Date.now() / 1000);
+    const presale = {
+      merkleRoot: allowlist.root,
+      start: new BN(now - 60),
+      end: new BN(now + 3600),
+      maxPerWallet: 1,
+    };
+
+    // The presale must end after it starts
+    await assertError(
+      program.rpc.setPresale(
+        { ...presale, end: presale.start },
+        {
+          accounts: {
+            exhibition: exhibition,
+            authority: exhibitor.publicKey,
+          },
+          signers: [exhibitor],
+        }
+      ),
+      program,
+      "InvalidPresaleWindowError"
+    );
+
+    await program.rpc.setPresale(presale, {
+      accounts: {
+        exhibition: exhibition,
+        authority: exhibitor.publicKey,
+      },
+      signers: [exhibitor],
+    });
+
+    const { presale: stored } = await program.account.exhibition.fetch(
+      exhibition
+    );
+    expect(Buffer.from(stored.merkleRoot).equals(Buffer.from(allowlist.root)))
+      .to.be.true;
+    expect(stored.maxPerWallet).to.equal(presale.maxPerWallet);
+  });
+
+  it("Buys an item", async () => {
     const [exhibition] = await web3.PublicKey.findProgramAddress(
       [
         Buffer.from("exhibition", "utf8"),
         mintKeys[indexRented].publicKey.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
       ],
       program.programId
     );
@@ -412,10 +1109,40 @@ describe("stead-rent", () => {
         program.programId
       );
 
+    const [buyerRecordKey, buyerRecordBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("buyer_record", "utf8"),
+          exhibition.toBuffer(),
+          buyer.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+    const [provenance] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("provenance", "utf8"),
+        mintKeys[indexDeposited].publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const [saleReceipt, saleReceiptBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("receipt", "utf8"),
+          mintKeys[indexDeposited].publicKey.toBuffer(),
+          new BN(0).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+
     const bumps = {
       item: exhibitionItemBump,
       tokenAccount: depositedTokenBump,
+      buyerRecord: buyerRecordBump,
+      saleReceipt: saleReceiptBump,
     };
+    const proof = allowlist.getProof(buyer.publicKey);
 
     const buyerAssociatedAccount = await mintKeys[
       indexDeposited
@@ -432,23 +1159,98 @@ describe("stead-rent", () => {
     const balanceDAOBefore = await provider.connection.getBalance(
       dao.publicKey
     );
+    const balancePayerBefore = await provider.connection.getBalance(
+      depositPayer.publicKey
+    );
 
-    await program.rpc.buyToken(bumps, {
-      accounts: {
-        state: state,
-        exhibition: exhibition,
-        exhibitor: exhibitor.publicKey,
-        exhibitionItem: exhibitionItemKey,
-        escrow: escrow,
-        depositedTokenMint: mintKeys[indexDeposited].publicKey,
-        depositedTokenAccount: depositedTokenKey,
-        buyer: buyer.publicKey,
-        buyerAccount: buyerAssociatedAccount.address,
-        renter: renter.publicKey,
-        dao: dao.publicKey,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        systemProgram: SystemProgram.programId,
-      },
+    const accounts = {
+      state: state,
+      exhibition: exhibition,
+      exhibitor: exhibitor.publicKey,
+      exhibitionItem: exhibitionItemKey,
+      escrow: escrow,
+      depositedTokenMint: mintKeys[indexDeposited].publicKey,
+      depositedTokenAccount: depositedTokenKey,
+      depositPayer: depositPayer.publicKey,
+      buyer: buyer.publicKey,
+      buyerRecord: buyerRecordKey,
+      provenance: provenance,
+      saleReceipt: saleReceipt,
+      buyerAccount: buyerAssociatedAccount.address,
+      renter: renter.publicKey,
+      dao: dao.publicKey,
+      priceOracle: priceOracle,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      rent: SYSVAR_RENT_PUBKEY,
+      systemProgram: SystemProgram.programId,
+    };
+    const remainingAccounts = [
+      { pubkey: curator.publicKey, isWritable: true, isSigner: false },
+      { pubkey: marketplace.publicKey, isWritable: true, isSigner: false },
+    ];
+
+    // Every share recipient must be provided
+    await assertError(
+      program.rpc.buyToken(bumps, proof, definedPrice, {
+        accounts,
+        signers: [buyer],
+      }),
+      program,
+      "ShareRecipientMismatchError"
+    );
+
+    // The exhibitor's share and the item's rent only go to the seller
+    await assertError(
+      program.rpc.buyToken(bumps, proof, definedPrice, {
+        accounts: { ...accounts, exhibitor: buyer.publicKey },
+        remainingAccounts,
+        signers: [buyer],
+      }),
+      program,
+      "NotSellerError"
+    );
+
+    // The renter's share only goes to the renter of the exhibition
+    await assertError(
+      program.rpc.buyToken(bumps, proof, definedPrice, {
+        accounts: { ...accounts, renter: buyer.publicKey },
+        remainingAccounts,
+        signers: [buyer],
+      }),
+      program,
+      "NotRenterError"
+    );
+
+    // The parties of the sale cannot take the referral fee
+    for (const party of [buyer, exhibitor, renter]) {
+      await assertError(
+        program.rpc.buyToken(bumps, proof, definedPrice, {
+          accounts,
+          remainingAccounts: [
+            remainingAccounts[0],
+            { pubkey: party.publicKey, isWritable: true, isSigner: false },
+          ],
+          signers: [buyer],
+        }),
+        program,
+        "InvalidReferrerError"
+      );
+    }
+
+    // The buyer must prove it is allowed during the presale
+    await assertError(
+      program.rpc.buyToken(bumps, [], definedPrice, {
+        accounts,
+        remainingAccounts,
+        signers: [buyer],
+      }),
+      program,
+      "NotAllowlistedError"
+    );
+
+    await program.rpc.buyToken(bumps, proof, definedPrice, {
+      accounts,
+      remainingAccounts,
       signers: [buyer],
     });
 
@@ -461,12 +1263,60 @@ describe("stead-rent", () => {
     const buyerAccount = await depositedToken.getAccountInfo(
       buyerAssociatedAccount.address
     );
-    const escrowAccount = await depositedToken.getAccountInfo(
-      depositedTokenKey
+    expect(buyerAccount.amount.toNumber()).to.equal(new BN(1).toNumber());
+
+    // The emptied escrow account is closed and its rent refunded
+    expect(
+      await provider.connection.getAccountInfo(depositedTokenKey)
+    ).to.equal(null);
+    expect(
+      await provider.connection.getBalance(depositPayer.publicKey)
+    ).to.be.above(balancePayerBefore);
+
+    const buyerRecord = await program.account.buyerRecord.fetch(
+      buyerRecordKey
     );
+    expect(buyerRecord.presalePurchases).to.equal(1);
 
-    expect(buyerAccount.amount.toNumber()).to.equal(new BN(1).toNumber());
-    expect(escrowAccount.amount.toNumber()).to.equal(new BN(0).toNumber());
+    // The record counts purchases until the presale ends
+    await assertError(
+      program.rpc.closeBuyerRecord({
+        accounts: {
+          exhibition: exhibition,
+          buyerRecord: buyerRecordKey,
+          buyer: buyer.publicKey,
+        },
+        signers: [buyer],
+      }),
+      program,
+      "PresaleRunningError"
+    );
+
+    const { stats } = await program.account.exhibition.fetch(exhibition);
+    expect(stats.piecesDeposited.toNumber()).to.equal(2);
+    expect(stats.piecesWithdrawn.toNumber()).to.equal(1);
+    expect(stats.piecesSold.toNumber()).to.equal(1);
+    expect(stats.volume.toNumber()).to.equal(definedPrice.toNumber());
+    expect(stats.paidToDao.toNumber()).to.equal(
+      definedPrice.toNumber() * 0.015
+    );
+
+    const { stats: globalStats } = await program.account.state.fetch(state);
+    expect(globalStats.piecesSold.toNumber()).to.equal(1);
+    expect(globalStats.volume.toNumber()).to.equal(definedPrice.toNumber());
+
+    const receipt = await program.account.saleReceipt.fetch(saleReceipt);
+    expect(receipt.buyer.toString()).to.equal(buyer.publicKey.toString());
+    expect(receipt.exhibitor.toString()).to.equal(
+      exhibitor.publicKey.toString()
+    );
+    expect(receipt.price.toNumber()).to.equal(definedPrice.toNumber());
+    expect(receipt.split.amountRenter.toNumber()).to.equal(
+      definedPrice.toNumber() * 0.025
+    );
+    expect(receipt.split.referrer.toString()).to.equal(
+      marketplace.publicKey.toString()
+    );
 
     const balance = await provider.connection.getBalance(buyer.publicKey);
     expect(balance <= initialBalance.sub(definedPrice).toNumber()).to.equal(
@@ -477,54 +1327,2385 @@ describe("stead-rent", () => {
     // of rent exemption given back on account closing
     expect(
       await provider.connection.getBalance(exhibitor.publicKey)
-    ).to.be.above(balanceExhibitorBefore + definedPrice.toNumber() * 0.95);
+    ).to.be.above(balanceExhibitorBefore + definedPrice.toNumber() * 0.9);
     expect(await provider.connection.getBalance(renter.publicKey)).to.equal(
       balanceRenterBefore + definedPrice.toNumber() * 0.025
     );
     expect(await provider.connection.getBalance(dao.publicKey)).to.equal(
-      balanceDAOBefore + definedPrice.toNumber() * 0.025
+      balanceDAOBefore + definedPrice.toNumber() * 0.015
+    );
+    expect(await provider.connection.getBalance(curator.publicKey)).to.equal(
+      definedPrice.toNumber() * 0.05
+    );
+    expect(await provider.connection.getBalance(marketplace.publicKey)).to.equal(
+      definedPrice.toNumber() * 0.01
     );
   });
 
-  it("Cancel", async () => {
+  it("Buys a lazily minted item", async () => {
     const [exhibition] = await web3.PublicKey.findProgramAddress(
       [
         Buffer.from("exhibition", "utf8"),
         mintKeys[indexRented].publicKey.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const [escrow] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("escrow", "utf8"),
+        mintKeys[indexRented].publicKey.toBuffer(),
       ],
       program.programId
     );
 
-    await program.rpc.cancelExhibition({
+    // The presale is over
+    await program.rpc.setPresale(null, {
       accounts: {
         exhibition: exhibition,
-        renter: renter.publicKey,
+        authority: exhibitor.publicKey,
       },
-      signers: [renter],
+      signers: [exhibitor],
     });
 
-    const { status } = await program.account.exhibition.fetch(exhibition);
+    const { nLazyListings } = await program.account.exhibition.fetch(
+      exhibition
+    );
+    const [lazyListing, lazyListingBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("lazy_listing", "utf8"),
+          exhibition.toBuffer(),
+          nLazyListings.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
 
-    expect('cancelled' in status).to.be.true;
+    const definedPrice = new BN(10 ** 8);
+
+    await program.rpc.listLazyToken(
+      lazyListingBump,
+      definedPrice,
+      "Unminted",
+      "LAZY",
+      "https://example.com/unminted.json",
+      [],
+      {
+        accounts: {
+          state: state,
+          exhibition: exhibition,
+          lazyListing: lazyListing,
+          exhibitor: exhibitor.publicKey,
+          payer: provider.wallet.publicKey,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [exhibitor],
+      }
+    );
+
+    const listing = await program.account.lazyListing.fetch(lazyListing);
+    expect(listing.price.toNumber()).to.equal(definedPrice.toNumber());
+    expect(listing.uri).to.equal("https://example.com/unminted.json");
+
+    const [mint, mintBump] = await web3.PublicKey.findProgramAddress(
+      [Buffer.from("lazy_mint", "utf8"), lazyListing.toBuffer()],
+      program.programId
+    );
+    const [metadata] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("metadata", "utf8"),
+        tokenMetadataProgramId.toBuffer(),
+        mint.toBuffer(),
+      ],
+      tokenMetadataProgramId
+    );
+    const [buyerRecord, buyerRecordBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("buyer_record", "utf8"),
+          exhibition.toBuffer(),
+          buyer.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const buyerAccount = await findAssociatedAddress(buyer.publicKey, mint);
+    const [provenance, provenanceBump] =
+      await web3.PublicKey.findProgramAddress(
+        [Buffer.from("provenance", "utf8"), mint.toBuffer()],
+        program.programId
+      );
+    const [provenanceEntry, provenanceEntryBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("provenance", "utf8"),
+          mint.toBuffer(),
+          new BN(0).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+    const [saleReceipt, saleReceiptBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("receipt", "utf8"),
+          mint.toBuffer(),
+          new BN(0).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+
+    await program.rpc.buyLazyToken(
+      {
+        mint: mintBump,
+        buyerRecord: buyerRecordBump,
+        provenance: provenanceBump,
+        provenanceEntry: provenanceEntryBump,
+        saleReceipt: saleReceiptBump,
+      },
+      false,
+      [],
+      {
+        accounts: {
+          state: state,
+          exhibition: exhibition,
+          exhibitor: exhibitor.publicKey,
+          lazyListing: lazyListing,
+          escrow: escrow,
+          mint: mint,
+          provenance: provenance,
+          provenanceEntry: provenanceEntry,
+          saleReceipt: saleReceipt,
+          metadata: metadata,
+          buyer: buyer.publicKey,
+          buyerRecord: buyerRecord,
+          buyerAccount: buyerAccount,
+          renter: renter.publicKey,
+          dao: dao.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenMetadataProgram: tokenMetadataProgramId,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        remainingAccounts: [
+          { pubkey: curator.publicKey, isWritable: true, isSigner: false },
+        ],
+        signers: [buyer],
+      }
+    );
+
+    const mintedToken = new Token(
+      provider.connection,
+      mint,
+      TOKEN_PROGRAM_ID,
+      buyer
+    );
+    const mintInfo = await mintedToken.getMintInfo();
+    expect(mintInfo.supply.toNumber()).to.equal(1);
+    expect(mintInfo.mintAuthority).to.be.null;
+
+    const account = await mintedToken.getAccountInfo(buyerAccount);
+    expect(account.amount.toNumber()).to.equal(1);
+
+    expect(
+      await provider.connection.getAccountInfo(lazyListing)
+    ).to.equal(null);
+
+    const receipt = await program.account.saleReceipt.fetch(saleReceipt);
+    expect(receipt.mint.toString()).to.equal(mint.toString());
+    expect(receipt.price.toNumber()).to.equal(definedPrice.toNumber());
+
+    // The buyer recovers the rent of its record once the presale is over
+    const balanceBuyer = await provider.connection.getBalance(buyer.publicKey);
+    await program.rpc.closeBuyerRecord({
+      accounts: {
+        exhibition: exhibition,
+        buyerRecord: buyerRecord,
+        buyer: buyer.publicKey,
+      },
+      signers: [buyer],
+    });
+    expect(await provider.connection.getAccountInfo(buyerRecord)).to.be.null;
+    expect(await provider.connection.getBalance(buyer.publicKey)).to.be.above(
+      balanceBuyer
+    );
   });
 
-  it("Close", async () => {
+  it("Buys an item priced in USD", async () => {
+    const indexOtherDeposit = 2;
+
     const [exhibition] = await web3.PublicKey.findProgramAddress(
       [
         Buffer.from("exhibition", "utf8"),
         mintKeys[indexRented].publicKey.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
       ],
       program.programId
     );
+    const [escrow] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("escrow", "utf8"),
+        mintKeys[indexRented].publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const [depositedTokenKey, depositedTokenBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("token_account", "utf8"),
+          mintKeys[indexOtherDeposit].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [exhibitionItemKey, exhibitionItemBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("item", "utf8"),
+          exhibition.toBuffer(),
+          mintKeys[indexOtherDeposit].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [provenance, provenanceBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("provenance", "utf8"),
+          mintKeys[indexOtherDeposit].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [provenanceEntry, provenanceEntryBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("provenance", "utf8"),
+          mintKeys[indexOtherDeposit].publicKey.toBuffer(),
+          new BN(1).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+    const [buyerRecordKey, buyerRecordBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("buyer_record", "utf8"),
+          exhibition.toBuffer(),
+          buyer.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [saleReceipt, saleReceiptBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("receipt", "utf8"),
+          mintKeys[indexOtherDeposit].publicKey.toBuffer(),
+          new BN(0).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
 
-    await program.rpc.closeExhibition({
-      accounts: {
-        exhibition: exhibition,
-        renter: renter.publicKey,
+    // $25.00, worth 0.25 SOL at the price of the local oracle
+    const definedPrice = new BN(2500);
+    const expectedLamports = new BN(25 * 10 ** 7);
+
+    await program.rpc.depositToken(
+      {
+        item: exhibitionItemBump,
+        tokenAccount: depositedTokenBump,
+        provenance: provenanceBump,
+        provenanceEntry: provenanceEntryBump,
       },
-      signers: [renter],
+      definedPrice,
+      usdCents,
+      { caption: "", position: 0, uri: null },
+      [],
+      {
+        accounts: {
+          state: state,
+          exhibition: exhibition,
+          exhibitionItem: exhibitionItemKey,
+          escrow: escrow,
+          depositedTokenMint: mintKeys[indexOtherDeposit].publicKey,
+          depositedTokenAccount: depositedTokenKey,
+          exhibitor: exhibitor.publicKey,
+          authority: exhibitor.publicKey,
+          delegation: exhibitor.publicKey,
+          provenance: provenance,
+          provenanceEntry: provenanceEntry,
+          exhibitorAccount: tokenAccounts[indexOtherDeposit],
+          payer: provider.wallet.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [exhibitor],
+      }
+    );
+
+    const buyerAssociatedAccount = await mintKeys[
+      indexOtherDeposit
+    ].getOrCreateAssociatedAccountInfo(buyer.publicKey);
+
+    const bumps = {
+      item: exhibitionItemBump,
+      tokenAccount: depositedTokenBump,
+      buyerRecord: buyerRecordBump,
+      saleReceipt: saleReceiptBump,
+    };
+    const accounts = {
+      state: state,
+      exhibition: exhibition,
+      exhibitor: exhibitor.publicKey,
+      exhibitionItem: exhibitionItemKey,
+      escrow: escrow,
+      depositedTokenMint: mintKeys[indexOtherDeposit].publicKey,
+      depositedTokenAccount: depositedTokenKey,
+      depositPayer: provider.wallet.publicKey,
+      buyer: buyer.publicKey,
+      buyerRecord: buyerRecordKey,
+      provenance: provenance,
+      saleReceipt: saleReceipt,
+      buyerAccount: buyerAssociatedAccount.address,
+      renter: renter.publicKey,
+      dao: dao.publicKey,
+      priceOracle: priceOracle,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      rent: SYSVAR_RENT_PUBKEY,
+      systemProgram: SystemProgram.programId,
+    };
+    const remainingAccounts = [
+      { pubkey: curator.publicKey, isWritable: true, isSigner: false },
+    ];
+
+    // The buyer caps what it pays
+    await assertError(
+      program.rpc.buyToken(bumps, [], expectedLamports.subn(1), {
+        accounts,
+        remainingAccounts,
+        signers: [buyer],
+      }),
+      program,
+      "PriceAboveMaximumError"
+    );
+
+    // Only the whitelisted oracle is read
+    await assertError(
+      program.rpc.buyToken(bumps, [], expectedLamports, {
+        accounts: { ...accounts, priceOracle: dao.publicKey },
+        remainingAccounts,
+        signers: [buyer],
+      }),
+      program,
+      "InvalidOracleError"
+    );
+
+    // The price of the local oracle is too old once the allowed age is lowered
+    const setMaxPriceAge = (maxAge: number) =>
+      program.rpc.setPriceOracle(priceOracle, new BN(maxAge), maxConfidence, {
+        accounts: {
+          state: state,
+          owner: dao.publicKey,
+        },
+        signers: [dao],
+      });
+    await setMaxPriceAge(60);
+    await assertError(
+      program.rpc.buyToken(bumps, [], expectedLamports, {
+        accounts,
+        remainingAccounts,
+        signers: [buyer],
+      }),
+      program,
+      "StalePriceError"
+    );
+    await setMaxPriceAge(maxPriceAge);
+
+    await program.rpc.buyToken(bumps, [], expectedLamports, {
+      accounts,
+      remainingAccounts,
+      signers: [buyer],
     });
 
-    await assertFail(program.account.exhibition.fetch(exhibition));
+    const receipt = await program.account.saleReceipt.fetch(saleReceipt);
+    expect(receipt.price.toNumber()).to.equal(expectedLamports.toNumber());
+
+    const buyerAccount = await mintKeys[indexOtherDeposit].getAccountInfo(
+      buyerAssociatedAccount.address
+    );
+    expect(buyerAccount.amount.toNumber()).to.equal(1);
+  });
+
+  it("Sells a bundle", async () => {
+    const [exhibition] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("exhibition", "utf8"),
+        mintKeys[indexRented].publicKey.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const [escrow] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("escrow", "utf8"),
+        mintKeys[indexRented].publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+
+    // A series of two pieces
+    const series = [];
+    for (let i = 0; i < 2; i++) {
+      const mint = await Token.createMint(
+        provider.connection,
+        exhibitor,
+        exhibitor.publicKey,
+        null,
+        0,
+        TOKEN_PROGRAM_ID
+      );
+      const exhibitorAccount = await mint.createAccount(exhibitor.publicKey);
+      await mint.mintTo(exhibitorAccount, exhibitor, [], 1);
+
+      const [depositedTokenKey, depositedTokenBump] =
+        await web3.PublicKey.findProgramAddress(
+          [Buffer.from("token_account", "utf8"), mint.publicKey.toBuffer()],
+          program.programId
+        );
+      const [exhibitionItemKey, exhibitionItemBump] =
+        await web3.PublicKey.findProgramAddress(
+          [
+            Buffer.from("item", "utf8"),
+            exhibition.toBuffer(),
+            mint.publicKey.toBuffer(),
+          ],
+          program.programId
+        );
+      const [provenance, provenanceBump] =
+        await web3.PublicKey.findProgramAddress(
+          [Buffer.from("provenance", "utf8"), mint.publicKey.toBuffer()],
+          program.programId
+        );
+      const [provenanceEntry, provenanceEntryBump] =
+        await web3.PublicKey.findProgramAddress(
+          [
+            Buffer.from("provenance", "utf8"),
+            mint.publicKey.toBuffer(),
+            new BN(0).toArrayLike(Buffer, "le", 8),
+          ],
+          program.programId
+        );
+
+      await program.rpc.depositToken(
+        {
+          item: exhibitionItemBump,
+          tokenAccount: depositedTokenBump,
+          provenance: provenanceBump,
+          provenanceEntry: provenanceEntryBump,
+        },
+        new BN(10 ** 9),
+        lamports,
+        { caption: "", position: 0, uri: null },
+        [],
+        {
+          accounts: {
+            state: state,
+            exhibition: exhibition,
+            exhibitionItem: exhibitionItemKey,
+            escrow: escrow,
+            depositedTokenMint: mint.publicKey,
+            depositedTokenAccount: depositedTokenKey,
+            exhibitor: exhibitor.publicKey,
+            authority: exhibitor.publicKey,
+            delegation: exhibitor.publicKey,
+            provenance: provenance,
+            provenanceEntry: provenanceEntry,
+            exhibitorAccount: exhibitorAccount,
+            payer: provider.wallet.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [exhibitor],
+        }
+      );
+
+      const buyerAccount = await mint.createAccount(buyer.publicKey);
+      const [saleReceipt] = await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("receipt", "utf8"),
+          mint.publicKey.toBuffer(),
+          new BN(0).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+      series.push({
+        mint,
+        exhibitionItemKey,
+        depositedTokenKey,
+        buyerAccount,
+        provenance,
+        saleReceipt,
+      });
+    }
+
+    const { nBundles } = await program.account.exhibition.fetch(exhibition);
+    const [bundle, bundleBump] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("bundle", "utf8"),
+        exhibition.toBuffer(),
+        nBundles.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const bundlePrice = new BN(15 * 10 ** 8);
+
+    await program.rpc.createBundle(bundleBump, bundlePrice, {
+      accounts: {
+        exhibition: exhibition,
+        bundle: bundle,
+        exhibitor: exhibitor.publicKey,
+        payer: provider.wallet.publicKey,
+        rent: SYSVAR_RENT_PUBKEY,
+        systemProgram: SystemProgram.programId,
+      },
+      remainingAccounts: series.map(({ exhibitionItemKey }) => ({
+        pubkey: exhibitionItemKey,
+        isWritable: true,
+        isSigner: false,
+      })),
+      signers: [exhibitor],
+    });
+
+    const { items } = await program.account.bundle.fetch(bundle);
+    expect(items.length).to.equal(2);
+
+    const item = await program.account.exhibitionItem.fetch(
+      series[0].exhibitionItemKey
+    );
+    expect(item.bundle.toString()).to.equal(bundle.toString());
+
+    const accounts = {
+      state: state,
+      exhibition: exhibition,
+      exhibitor: exhibitor.publicKey,
+      bundle: bundle,
+      escrow: escrow,
+      buyer: buyer.publicKey,
+      renter: renter.publicKey,
+      dao: dao.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    };
+    const itemAccounts = series.flatMap(
+      ({
+        exhibitionItemKey,
+        depositedTokenKey,
+        buyerAccount,
+        provenance,
+        saleReceipt,
+      }) => [
+        { pubkey: exhibitionItemKey, isWritable: true, isSigner: false },
+        { pubkey: depositedTokenKey, isWritable: true, isSigner: false },
+        { pubkey: buyerAccount, isWritable: true, isSigner: false },
+        {
+          pubkey: provider.wallet.publicKey,
+          isWritable: true,
+          isSigner: false,
+        },
+        { pubkey: provenance, isWritable: true, isSigner: false },
+        { pubkey: saleReceipt, isWritable: true, isSigner: false },
+      ]
+    );
+    const shareAccounts = [
+      { pubkey: curator.publicKey, isWritable: true, isSigner: false },
+    ];
+
+    // Every bundled item must be provided
+    await assertError(
+      program.rpc.buyBundle({
+        accounts,
+        remainingAccounts: [...itemAccounts.slice(6), ...shareAccounts],
+        signers: [buyer],
+      }),
+      program,
+      "InvalidBundleError"
+    );
+
+    // Receipts are created at the next sale number of each piece
+    await assertError(
+      program.rpc.buyBundle({
+        accounts,
+        remainingAccounts: [
+          ...itemAccounts.slice(0, 5),
+          itemAccounts[11],
+          ...itemAccounts.slice(6, 11),
+          itemAccounts[5],
+          ...shareAccounts,
+        ],
+        signers: [buyer],
+      }),
+      program,
+      "InvalidBundleError"
+    );
+
+    // Funding a receipt address beforehand does not block the sale
+    await provider.send(
+      new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: provider.wallet.publicKey,
+          toPubkey: series[0].saleReceipt,
+          lamports: 10 ** 6,
+        })
+      )
+    );
+
+    const balanceRenterBefore = await provider.connection.getBalance(
+      renter.publicKey
+    );
+
+    await program.rpc.buyBundle({
+      accounts,
+      remainingAccounts: [...itemAccounts, ...shareAccounts],
+      signers: [buyer],
+    });
+
+    for (const piece of series) {
+      const account = await piece.mint.getAccountInfo(piece.buyerAccount);
+      expect(account.amount.toNumber()).to.equal(1);
+      expect(
+        await provider.connection.getAccountInfo(piece.exhibitionItemKey)
+      ).to.be.null;
+
+      // Each piece has a receipt of the bundle purchase and one more sale
+      const receipt = await program.account.saleReceipt.fetch(
+        piece.saleReceipt
+      );
+      expect(receipt.mint.toString()).to.equal(piece.mint.publicKey.toString());
+      expect(receipt.buyer.toString()).to.equal(buyer.publicKey.toString());
+      expect(receipt.bundle.toString()).to.equal(bundle.toString());
+      expect(receipt.price.toNumber()).to.equal(bundlePrice.toNumber());
+      const { nSales } = await program.account.provenance.fetch(
+        piece.provenance
+      );
+      expect(nSales.toNumber()).to.equal(1);
+    }
+    expect(await provider.connection.getAccountInfo(bundle)).to.be.null;
+
+    expect(await provider.connection.getBalance(renter.publicKey)).to.equal(
+      balanceRenterBefore + bundlePrice.toNumber() * 0.025
+    );
+  });
+
+  it("Sells tickets", async () => {
+    const [exhibition] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("exhibition", "utf8"),
+        mintKeys[indexRented].publicKey.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const [ticket, ticketBump] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("ticket", "utf8"),
+        exhibition.toBuffer(),
+        buyer.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+
+    const ticketing = {
+      price: new BN(10 ** 7),
+      validity: new BN(3600),
+      exhibitorFee: 2000,
+    };
+
+    // The exhibitor and DAO cannot take more than the entrance fee
+    await assertError(
+      program.rpc.setTicketing(
+        { ...ticketing, exhibitorFee: 10000 },
+        {
+          accounts: {
+            state: state,
+            exhibition: exhibition,
+            renter: renter.publicKey,
+          },
+          signers: [renter],
+        }
+      ),
+      program,
+      "FeeOutOfRangeError"
+    );
+
+    await program.rpc.setTicketing(ticketing, {
+      accounts: {
+        state: state,
+        exhibition: exhibition,
+        renter: renter.publicKey,
+      },
+      signers: [renter],
+    });
+
+    const balanceRenterBefore = await provider.connection.getBalance(
+      renter.publicKey
+    );
+    const balanceExhibitorBefore = await provider.connection.getBalance(
+      exhibitor.publicKey
+    );
+
+    await program.rpc.buyTicket(ticketBump, {
+      accounts: {
+        state: state,
+        exhibition: exhibition,
+        ticket: ticket,
+        visitor: buyer.publicKey,
+        renter: renter.publicKey,
+        exhibitor: exhibitor.publicKey,
+        dao: dao.publicKey,
+        rent: SYSVAR_RENT_PUBKEY,
+        systemProgram: SystemProgram.programId,
+      },
+      signers: [buyer],
+    });
+
+    const { visitor, expiresAt } = await program.account.ticket.fetch(ticket);
+    expect(visitor.toString()).to.equal(buyer.publicKey.toString());
+    expect(expiresAt.toNumber()).to.be.above(Date.now() / 1000);
+
+    expect(await provider.connection.getBalance(renter.publicKey)).to.equal(
+      balanceRenterBefore + ticketing.price.toNumber() * 0.775
+    );
+    expect(await provider.connection.getBalance(exhibitor.publicKey)).to.equal(
+      balanceExhibitorBefore + ticketing.price.toNumber() * 0.2
+    );
+
+    // The DAO splits its fees between treasury wallets
+    const treasuries = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+    const weights = [6000, 3000, 1000];
+    for (const treasury of treasuries) {
+      const airdrop = await provider.connection.requestAirdrop(
+        treasury.publicKey,
+        initialBalance.toNumber()
+      );
+      await provider.connection.confirmTransaction(airdrop);
+    }
+    const feeRecipients = treasuries.map((treasury, i) => ({
+      recipient: treasury.publicKey,
+      basisPoints: weights[i],
+    }));
+
+    // Weights must add up to the whole fees
+    await assertError(
+      program.rpc.setFeeRecipients(
+        feeRecipients.map((share) => ({ ...share, basisPoints: 3000 })),
+        {
+          accounts: { state: state, owner: dao.publicKey },
+          signers: [dao],
+        }
+      ),
+      program,
+      "InvalidFeeRecipientsError"
+    );
+
+    await program.rpc.setFeeRecipients(feeRecipients, {
+      accounts: { state: state, owner: dao.publicKey },
+      signers: [dao],
+    });
+
+    const buyTicket = (remainingAccounts) =>
+      program.rpc.buyTicket(ticketBump, {
+        accounts: {
+          state: state,
+          exhibition: exhibition,
+          ticket: ticket,
+          visitor: buyer.publicKey,
+          renter: renter.publicKey,
+          exhibitor: exhibitor.publicKey,
+          dao: dao.publicKey,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        remainingAccounts,
+        signers: [buyer],
+      });
+    const recipientAccounts = treasuries.map((treasury) => ({
+      pubkey: treasury.publicKey,
+      isWritable: true,
+      isSigner: false,
+    }));
+
+    await assertError(
+      buyTicket(recipientAccounts.slice(1)),
+      program,
+      "FeeRecipientMismatchError"
+    );
+    await assertError(
+      buyTicket([...recipientAccounts].reverse()),
+      program,
+      "FeeRecipientMismatchError"
+    );
+
+    const balancesBefore = await Promise.all(
+      treasuries.map((treasury) =>
+        provider.connection.getBalance(treasury.publicKey)
+      )
+    );
+    const balanceDaoBefore = await provider.connection.getBalance(
+      dao.publicKey
+    );
+    await buyTicket(recipientAccounts);
+
+    const fees = (ticketing.price.toNumber() * feeAmount) / 10000;
+    for (let i = 0; i < treasuries.length; i++) {
+      expect(
+        await provider.connection.getBalance(treasuries[i].publicKey)
+      ).to.equal(balancesBefore[i] + (fees * weights[i]) / 10000);
+    }
+    expect(await provider.connection.getBalance(dao.publicKey)).to.equal(
+      balanceDaoBefore
+    );
+
+    // Fees go back to the fee earner
+    await program.rpc.setFeeRecipients([], {
+      accounts: { state: state, owner: dao.publicKey },
+      signers: [dao],
+    });
+    const { feeRecipients: cleared } = await program.account.state.fetch(state);
+    expect(cleared).to.be.empty;
+
+    // The fee is bounded by a minimum and a maximum in lamports
+    const setFeeBounds = (minFee, maxFee) =>
+      program.rpc.setState(
+        dao.publicKey,
+        feeAmount,
+        referralFee,
+        new BN(minFee),
+        maxFee === null ? null : new BN(maxFee),
+        false,
+        {
+          accounts: { state: state, owner: dao.publicKey },
+          signers: [dao],
+        }
+      );
+    const expectFee = async (fee) => {
+      const balanceDao = await provider.connection.getBalance(dao.publicKey);
+      const balanceRenter = await provider.connection.getBalance(
+        renter.publicKey
+      );
+      await buyTicket([]);
+      expect(await provider.connection.getBalance(dao.publicKey)).to.equal(
+        balanceDao + fee
+      );
+      expect(await provider.connection.getBalance(renter.publicKey)).to.equal(
+        balanceRenter + ticketing.price.toNumber() * 0.8 - fee
+      );
+    };
+
+    // 250 bps of the ticket price is 250000 lamports
+    await setFeeBounds(300000, null);
+    await expectFee(300000);
+    await setFeeBounds(0, 100000);
+    await expectFee(100000);
+    await setFeeBounds(fees, fees);
+    await expectFee(fees);
+
+    // The renter and exhibitor portions must cover the minimum fee
+    await setFeeBounds(ticketing.price.toNumber() * 0.8 + 1, null);
+    await assertError(buyTicket([]), program, "PriceBelowMinimumFeeError");
+
+    await setFeeBounds(0, null);
+  });
+
+  it("Bounds the fee of a sale", async () => {
+    const find = async (...seeds: Buffer[]) =>
+      PublicKey.findProgramAddress(seeds, program.programId);
+    const [exhibition] = await find(
+      Buffer.from("exhibition", "utf8"),
+      mintKeys[indexRented].publicKey.toBuffer(),
+      new BN(0).toArrayLike(Buffer, "le", 8)
+    );
+    const [escrow] = await find(
+      Buffer.from("escrow", "utf8"),
+      mintKeys[indexRented].publicKey.toBuffer()
+    );
+
+    // Mints a new piece and lists it in the exhibition
+    const listPiece = async (price: number) => {
+      const piece = await Token.createMint(
+        provider.connection,
+        exhibitor,
+        exhibitor.publicKey,
+        null,
+        0,
+        TOKEN_PROGRAM_ID
+      );
+      const exhibitorAccount = await piece.createAccount(exhibitor.publicKey);
+      await piece.mintTo(exhibitorAccount, exhibitor, [], 1);
+      const mint = piece.publicKey.toBuffer();
+
+      const [tokenAccount, tokenAccountBump] = await find(
+        Buffer.from("token_account", "utf8"),
+        mint
+      );
+      const [item, itemBump] = await find(
+        Buffer.from("item", "utf8"),
+        exhibition.toBuffer(),
+        mint
+      );
+      const [provenance, provenanceBump] = await find(
+        Buffer.from("provenance", "utf8"),
+        mint
+      );
+      const [provenanceEntry, provenanceEntryBump] = await find(
+        Buffer.from("provenance", "utf8"),
+        mint,
+        new BN(0).toArrayLike(Buffer, "le", 8)
+      );
+      await program.rpc.depositToken(
+        {
+          item: itemBump,
+          tokenAccount: tokenAccountBump,
+          provenance: provenanceBump,
+          provenanceEntry: provenanceEntryBump,
+        },
+        new BN(price),
+        lamports,
+        { caption: "", position: 0, uri: null },
+        [],
+        {
+          accounts: {
+            state: state,
+            exhibition: exhibition,
+            exhibitionItem: item,
+            escrow: escrow,
+            depositedTokenMint: piece.publicKey,
+            depositedTokenAccount: tokenAccount,
+            exhibitor: exhibitor.publicKey,
+            authority: exhibitor.publicKey,
+            delegation: exhibitor.publicKey,
+            provenance: provenance,
+            provenanceEntry: provenanceEntry,
+            exhibitorAccount: exhibitorAccount,
+            payer: provider.wallet.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [exhibitor],
+        }
+      );
+
+      const [buyerRecord, buyerRecordBump] = await find(
+        Buffer.from("buyer_record", "utf8"),
+        exhibition.toBuffer(),
+        buyer.publicKey.toBuffer()
+      );
+      const [saleReceipt, saleReceiptBump] = await find(
+        Buffer.from("receipt", "utf8"),
+        mint,
+        new BN(0).toArrayLike(Buffer, "le", 8)
+      );
+      const buyerAccount = await piece.createAccount(buyer.publicKey);
+
+      // Buys the piece, passing the share recipients then the fee recipients and referrer
+      const buy = (remainingAccounts: PublicKey[]) =>
+        program.rpc.buyToken(
+          {
+            item: itemBump,
+            tokenAccount: tokenAccountBump,
+            buyerRecord: buyerRecordBump,
+            saleReceipt: saleReceiptBump,
+          },
+          [],
+          new BN(price),
+          {
+            accounts: {
+              state: state,
+              exhibition: exhibition,
+              exhibitor: exhibitor.publicKey,
+              exhibitionItem: item,
+              escrow: escrow,
+              depositedTokenMint: piece.publicKey,
+              depositedTokenAccount: tokenAccount,
+              depositPayer: provider.wallet.publicKey,
+              buyer: buyer.publicKey,
+              buyerRecord: buyerRecord,
+              provenance: provenance,
+              saleReceipt: saleReceipt,
+              buyerAccount: buyerAccount,
+              renter: renter.publicKey,
+              dao: dao.publicKey,
+              priceOracle: priceOracle,
+              tokenProgram: TOKEN_PROGRAM_ID,
+              rent: SYSVAR_RENT_PUBKEY,
+              systemProgram: SystemProgram.programId,
+            },
+            remainingAccounts: [curator.publicKey, ...remainingAccounts].map(
+              (pubkey) => ({ pubkey, isWritable: true, isSigner: false })
+            ),
+            signers: [buyer],
+          }
+        );
+      const split = async () =>
+        (await program.account.saleReceipt.fetch(saleReceipt)).split;
+
+      return { buy, split };
+    };
+    const setFeeBounds = (minFee: number, maxFee: number | null) =>
+      program.rpc.setState(
+        dao.publicKey,
+        feeAmount,
+        referralFee,
+        new BN(minFee),
+        maxFee === null ? null : new BN(maxFee),
+        false,
+        {
+          accounts: { state: state, owner: dao.publicKey },
+          signers: [dao],
+        }
+      );
+
+    // The renter and curator take 750 bps, the fee can take the rest
+    const price = 10 ** 6;
+    const left = price - (price * (renterFee + curatorFee)) / 10000;
+    const cheap = await listPiece(price);
+    await setFeeBounds(left + 1, null);
+    await assertError(cheap.buy([]), program, "PriceBelowMinimumFeeError");
+
+    await setFeeBounds(left, null);
+    const balanceDao = await provider.connection.getBalance(dao.publicKey);
+    await cheap.buy([]);
+    let split = await cheap.split();
+    expect(split.amountFeeEarner.toNumber()).to.equal(left);
+    expect(split.amountExhibitor.toNumber()).to.equal(0);
+    expect(split.amountShares.toNumber()).to.equal((price * curatorFee) / 10000);
+    expect(await provider.connection.getBalance(dao.publicKey)).to.equal(
+      balanceDao + left
+    );
+
+    // Out of 0.01 SOL, the renter and curator take 750000 lamports and
+    // the 250 bps fee of 250000 lamports is capped to 10000
+    const expensive = await listPiece(10 ** 7);
+    await setFeeBounds(0, 10000);
+    await expensive.buy([]);
+    split = await expensive.split();
+    expect(split.amountFeeEarner.toNumber()).to.equal(10000);
+    expect(split.amountExhibitor.toNumber()).to.equal(9250000 - 10000);
+
+    // The referrer takes its 100 bps out of the capped fee, then the fee recipients split the rest
+    const treasuries = [Keypair.generate(), Keypair.generate()];
+    for (const treasury of treasuries) {
+      const airdrop = await provider.connection.requestAirdrop(
+        treasury.publicKey,
+        initialBalance.toNumber()
+      );
+      await provider.connection.confirmTransaction(airdrop);
+    }
+    await program.rpc.setFeeRecipients(
+      [
+        { recipient: treasuries[0].publicKey, basisPoints: 6000 },
+        { recipient: treasuries[1].publicKey, basisPoints: 4000 },
+      ],
+      {
+        accounts: { state: state, owner: dao.publicKey },
+        signers: [dao],
+      }
+    );
+    await setFeeBounds(0, 200000);
+
+    const referred = await listPiece(10 ** 7);
+    const balances = await Promise.all(
+      [marketplace, ...treasuries].map((wallet) =>
+        provider.connection.getBalance(wallet.publicKey)
+      )
+    );
+    await referred.buy([
+      ...treasuries.map((treasury) => treasury.publicKey),
+      marketplace.publicKey,
+    ]);
+    split = await referred.split();
+    expect(split.amountReferrer.toNumber()).to.equal(100000);
+    expect(split.amountFeeEarner.toNumber()).to.equal(100000);
+    expect(split.amountExhibitor.toNumber()).to.equal(9250000 - 200000);
+    expect(
+      await provider.connection.getBalance(marketplace.publicKey)
+    ).to.equal(balances[0] + 100000);
+    expect(
+      await provider.connection.getBalance(treasuries[0].publicKey)
+    ).to.equal(balances[1] + 60000);
+    expect(
+      await provider.connection.getBalance(treasuries[1].publicKey)
+    ).to.equal(balances[2] + 40000);
+
+    await program.rpc.setFeeRecipients([], {
+      accounts: { state: state, owner: dao.publicKey },
+      signers: [dao],
+    });
+    await setFeeBounds(0, null);
+  });
+
+  it("Deposits an item left in the exhibition", async () => {
+    const [exhibition] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("exhibition", "utf8"),
+        mintKeys[indexRented].publicKey.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const [escrow] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("escrow", "utf8"),
+        mintKeys[indexRented].publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const [depositedTokenKey, depositedTokenBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("token_account", "utf8"),
+          mintKeys[indexReturned].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [exhibitionItemKey, exhibitionItemBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("item", "utf8"),
+          exhibition.toBuffer(),
+          mintKeys[indexReturned].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [provenance, provenanceBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("provenance", "utf8"),
+          mintKeys[indexReturned].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [provenanceEntry, provenanceEntryBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("provenance", "utf8"),
+          mintKeys[indexReturned].publicKey.toBuffer(),
+          new BN(0).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+
+    const bumps = {
+      item: exhibitionItemBump,
+      tokenAccount: depositedTokenBump,
+      provenance: provenanceBump,
+      provenanceEntry: provenanceEntryBump,
+    };
+    const metadata = { caption: "", position: 1, uri: null };
+
+    await program.rpc.depositToken(bumps, new BN(10 ** 9), lamports, metadata, [], {
+      accounts: {
+        state: state,
+        exhibition: exhibition,
+        exhibitionItem: exhibitionItemKey,
+        escrow: escrow,
+        depositedTokenMint: mintKeys[indexReturned].publicKey,
+        depositedTokenAccount: depositedTokenKey,
+        exhibitor: exhibitor.publicKey,
+        authority: exhibitor.publicKey,
+        delegation: exhibitor.publicKey,
+        provenance: provenance,
+        provenanceEntry: provenanceEntry,
+        exhibitorAccount: tokenAccounts[indexReturned],
+        payer: provider.wallet.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+        systemProgram: SystemProgram.programId,
+      },
+      signers: [exhibitor],
+    });
+
+    // Pieces cannot be returned while the exhibition is active
+    await assertError(
+      program.rpc.returnToken(
+        { tokenAccount: depositedTokenBump },
+        {
+          accounts: {
+            state: state,
+            exhibition: exhibition,
+            exhibitor: exhibitor.publicKey,
+            exhibitorAccount: await findAssociatedAddress(
+              exhibitor.publicKey,
+              mintKeys[indexReturned].publicKey
+            ),
+            exhibitionItem: exhibitionItemKey,
+            escrow: escrow,
+            depositedTokenMint: mintKeys[indexReturned].publicKey,
+            depositedTokenAccount: depositedTokenKey,
+            depositPayer: provider.wallet.publicKey,
+            cranker: renter.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [renter],
+        }
+      ),
+      program,
+      "NoticePeriodNotElapsedError"
+    );
+
+    // Another piece of the series is left bundled with it
+    const companion = await Token.createMint(
+      provider.connection,
+      exhibitor,
+      exhibitor.publicKey,
+      null,
+      0,
+      TOKEN_PROGRAM_ID
+    );
+    const companionAccount = await companion.createAccount(exhibitor.publicKey);
+    await companion.mintTo(companionAccount, exhibitor, [], 1);
+    const [companionTokenKey, companionTokenBump] =
+      await web3.PublicKey.findProgramAddress(
+        [Buffer.from("token_account", "utf8"), companion.publicKey.toBuffer()],
+        program.programId
+      );
+    const [companionItemKey, companionItemBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("item", "utf8"),
+          exhibition.toBuffer(),
+          companion.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [companionProvenance, companionProvenanceBump] =
+      await web3.PublicKey.findProgramAddress(
+        [Buffer.from("provenance", "utf8"), companion.publicKey.toBuffer()],
+        program.programId
+      );
+    const [companionEntry, companionEntryBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("provenance", "utf8"),
+          companion.publicKey.toBuffer(),
+          new BN(0).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+
+    await program.rpc.depositToken(
+      {
+        item: companionItemBump,
+        tokenAccount: companionTokenBump,
+        provenance: companionProvenanceBump,
+        provenanceEntry: companionEntryBump,
+      },
+      new BN(10 ** 9),
+      lamports,
+      { caption: "", position: 2, uri: null },
+      [],
+      {
+        accounts: {
+          state: state,
+          exhibition: exhibition,
+          exhibitionItem: companionItemKey,
+          escrow: escrow,
+          depositedTokenMint: companion.publicKey,
+          depositedTokenAccount: companionTokenKey,
+          exhibitor: exhibitor.publicKey,
+          authority: exhibitor.publicKey,
+          delegation: exhibitor.publicKey,
+          provenance: companionProvenance,
+          provenanceEntry: companionEntry,
+          exhibitorAccount: companionAccount,
+          payer: provider.wallet.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [exhibitor],
+      }
+    );
+
+    const { nBundles } = await program.account.exhibition.fetch(exhibition);
+    const [bundle, bundleBump] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("bundle", "utf8"),
+        exhibition.toBuffer(),
+        nBundles.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const bundledItems = [exhibitionItemKey, companionItemKey].map(
+      (pubkey) => ({ pubkey, isWritable: true, isSigner: false })
+    );
+    await program.rpc.createBundle(bundleBump, new BN(2 * 10 ** 9), {
+      accounts: {
+        exhibition: exhibition,
+        bundle: bundle,
+        exhibitor: exhibitor.publicKey,
+        payer: provider.wallet.publicKey,
+        rent: SYSVAR_RENT_PUBKEY,
+        systemProgram: SystemProgram.programId,
+      },
+      remainingAccounts: bundledItems,
+      signers: [exhibitor],
+    });
+
+    // Only the seller and the renter dissolve bundles of an active exhibition
+    await assertError(
+      program.rpc.dissolveBundle({
+        accounts: {
+          bundle: bundle,
+          exhibition: exhibition,
+          exhibitor: exhibitor.publicKey,
+          authority: buyer.publicKey,
+        },
+        remainingAccounts: bundledItems,
+        signers: [buyer],
+      }),
+      program,
+      "BundleLockedError"
+    );
+
+    leftBundle = {
+      bundle,
+      bundledItems,
+      mint: companion,
+      tokenAccount: companionTokenKey,
+      tokenAccountBump: companionTokenBump,
+      item: companionItemKey,
+    };
+  });
+
+  it("Cancel", async () => {
+    const [exhibition] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("exhibition", "utf8"),
+        mintKeys[indexRented].publicKey.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+
+    await program.rpc.cancelExhibition({
+      accounts: {
+        exhibition: exhibition,
+        renter: renter.publicKey,
+        authority: renter.publicKey,
+        delegation: renter.publicKey,
+      },
+      signers: [renter],
+    });
+
+    const { status, cancelledAt } = await program.account.exhibition.fetch(
+      exhibition
+    );
+
+    expect('cancelled' in status).to.be.true;
+
+    // Cancelling again would push back the notice period
+    await assertError(
+      program.rpc.cancelExhibition({
+        accounts: {
+          exhibition: exhibition,
+          renter: renter.publicKey,
+          authority: renter.publicKey,
+          delegation: renter.publicKey,
+        },
+        signers: [renter],
+      }),
+      program,
+      "ExhibitionNotActiveError"
+    );
+    const { cancelledAt: unchanged } = await program.account.exhibition.fetch(
+      exhibition
+    );
+    expect(unchanged.toNumber()).to.equal(cancelledAt.toNumber());
+  });
+
+  it("Returns items after the notice period", async () => {
+    const [exhibition] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("exhibition", "utf8"),
+        mintKeys[indexRented].publicKey.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const [escrow] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("escrow", "utf8"),
+        mintKeys[indexRented].publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const [depositedTokenKey, depositedTokenBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("token_account", "utf8"),
+          mintKeys[indexReturned].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [exhibitionItemKey] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("item", "utf8"),
+        exhibition.toBuffer(),
+        mintKeys[indexReturned].publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const exhibitorAssociatedAccount = await findAssociatedAddress(
+      exhibitor.publicKey,
+      mintKeys[indexReturned].publicKey
+    );
+
+    const returnToken = async (mint, tokenAccount, tokenAccountBump, item) =>
+      program.rpc.returnToken(
+        { tokenAccount: tokenAccountBump },
+        {
+          accounts: {
+            state: state,
+            exhibition: exhibition,
+            exhibitor: exhibitor.publicKey,
+            exhibitorAccount: await findAssociatedAddress(
+              exhibitor.publicKey,
+              mint
+            ),
+            exhibitionItem: item,
+            escrow: escrow,
+            depositedTokenMint: mint,
+            depositedTokenAccount: tokenAccount,
+            depositPayer: provider.wallet.publicKey,
+            cranker: renter.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [renter],
+        }
+      );
+
+    // Bundled pieces are released before being returned
+    await assertError(
+      returnToken(
+        mintKeys[indexReturned].publicKey,
+        depositedTokenKey,
+        depositedTokenBump,
+        exhibitionItemKey
+      ),
+      program,
+      "ItemBundledError"
+    );
+
+    // Every bundled item must be passed in the order of the bundle
+    await assertError(
+      program.rpc.dissolveBundle({
+        accounts: {
+          bundle: leftBundle.bundle,
+          exhibition: exhibition,
+          exhibitor: exhibitor.publicKey,
+          authority: buyer.publicKey,
+        },
+        remainingAccounts: [...leftBundle.bundledItems].reverse(),
+        signers: [buyer],
+      }),
+      program,
+      "InvalidBundleError"
+    );
+
+    // Anyone dissolves the bundle once the notice period elapsed
+    await program.rpc.dissolveBundle({
+      accounts: {
+        bundle: leftBundle.bundle,
+        exhibition: exhibition,
+        exhibitor: exhibitor.publicKey,
+        authority: buyer.publicKey,
+      },
+      remainingAccounts: leftBundle.bundledItems,
+      signers: [buyer],
+    });
+    expect(await provider.connection.getAccountInfo(leftBundle.bundle)).to.be
+      .null;
+
+    await returnToken(
+      mintKeys[indexReturned].publicKey,
+      depositedTokenKey,
+      depositedTokenBump,
+      exhibitionItemKey
+    );
+    await returnToken(
+      leftBundle.mint.publicKey,
+      leftBundle.tokenAccount,
+      leftBundle.tokenAccountBump,
+      leftBundle.item
+    );
+
+    const returnedToken = new Token(
+      provider.connection,
+      mintKeys[indexReturned].publicKey,
+      TOKEN_PROGRAM_ID,
+      exhibitor
+    );
+    const exhibitorAccount = await returnedToken.getAccountInfo(
+      exhibitorAssociatedAccount
+    );
+    expect(exhibitorAccount.amount.toNumber()).to.equal(1);
+
+    expect(
+      await provider.connection.getAccountInfo(exhibitionItemKey)
+    ).to.equal(null);
+
+    const { nPieces } = await program.account.exhibition.fetch(exhibition);
+    expect(nPieces.toNumber()).to.equal(0);
+  });
+
+  it("Close", async () => {
+    const [exhibition] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("exhibition", "utf8"),
+        mintKeys[indexRented].publicKey.toBuffer(),
+        new BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+
+    const [stead] = await web3.PublicKey.findProgramAddress(
+      [Buffer.from("stead", "utf8"), mintKeys[indexRented].publicKey.toBuffer()],
+      program.programId
+    );
+    const [escrow] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("escrow", "utf8"),
+        mintKeys[indexRented].publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const [exhibitionTokenKey] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("token_account", "utf8"),
+        mintKeys[indexRented].publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+
+    await program.rpc.closeExhibition({
+      accounts: {
+        stead: stead,
+        exhibition: exhibition,
+        renter: renter.publicKey,
+        renterAccount: tokenAccounts[indexRented],
+        escrow: escrow,
+        exhibitionTokenAccount: exhibitionTokenKey,
+        depositPayer: provider.wallet.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      },
+      signers: [renter],
+    });
+
+    expect(await provider.connection.getAccountInfo(exhibition)).to.equal(null);
+
+    // The last room is closed, the stead goes back to the renter
+    const steadToken = new Token(
+      provider.connection,
+      mintKeys[indexRented].publicKey,
+      TOKEN_PROGRAM_ID,
+      renter
+    );
+    const renterAccount = await steadToken.getAccountInfo(
+      tokenAccounts[indexRented]
+    );
+    expect(renterAccount.amount.toNumber()).to.equal(1);
+
+    const { nOpenRooms } = await program.account.stead.fetch(stead);
+    expect(nOpenRooms).to.equal(0);
+  });
+
+  it("Withdraws a proposal", async () => {
+    const [stead, steadBump] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("stead", "utf8"),
+        mintKeys[indexProposed].publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const [exhibition, exhibitionBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("exhibition", "utf8"),
+          mintKeys[indexProposed].publicKey.toBuffer(),
+          new BN(0).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+    const [escrow, escrowBump] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("escrow", "utf8"),
+        mintKeys[indexProposed].publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const [exhibitionTokenKey, exhibitionTokenBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("token_account", "utf8"),
+          mintKeys[indexProposed].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+    await program.rpc.initializeExhibition(
+      {
+        stead: steadBump,
+        exhibition: exhibitionBump,
+        escrow: escrowBump,
+        exhibitionToken: exhibitionTokenBump,
+      },
+      renterFee,
+      [],
+      new BN(noticePeriod),
+      { exhibitor: {} },
+      false,
+      {
+        accounts: {
+          state: state,
+          stead: stead,
+          exhibition: exhibition,
+          escrow: escrow,
+          exhibitionTokenMint: mintKeys[indexProposed].publicKey,
+          exhibitionTokenAccount: exhibitionTokenKey,
+          renter: renter.publicKey,
+          renterAccount: tokenAccounts[indexProposed],
+          exhibitor: exhibitor.publicKey,
+          payer: provider.wallet.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [renter],
+      }
+    );
+
+    // A proposal is withdrawn rather than cancelled
+    await assertError(
+      program.rpc.cancelExhibition({
+        accounts: {
+          exhibition: exhibition,
+          renter: renter.publicKey,
+          authority: renter.publicKey,
+          delegation: renter.publicKey,
+        },
+        signers: [renter],
+      }),
+      program,
+      "ExhibitionNotActiveError"
+    );
+
+    // The exhibitor declines the terms
+    await program.rpc.withdrawProposal(
+      { exhibitionToken: exhibitionTokenBump },
+      {
+        accounts: {
+          stead: stead,
+          exhibition: exhibition,
+          authority: exhibitor.publicKey,
+          renter: renter.publicKey,
+          renterAccount: tokenAccounts[indexProposed],
+          escrow: escrow,
+          exhibitionTokenAccount: exhibitionTokenKey,
+          depositPayer: provider.wallet.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+        signers: [exhibitor],
+      }
+    );
+
+    const steadToken = new Token(
+      provider.connection,
+      mintKeys[indexProposed].publicKey,
+      TOKEN_PROGRAM_ID,
+      renter
+    );
+    const renterAccount = await steadToken.getAccountInfo(
+      tokenAccounts[indexProposed]
+    );
+    expect(renterAccount.amount.toNumber()).to.equal(1);
+
+    expect(await provider.connection.getAccountInfo(exhibition)).to.equal(null);
+    expect(
+      await provider.connection.getAccountInfo(exhibitionTokenKey)
+    ).to.equal(null);
+  });
+
+  it("Runs a curated group show", async () => {
+    const [stead, steadBump] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("stead", "utf8"),
+        mintKeys[indexProposed].publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const artist = Keypair.generate();
+    const airdrop = await provider.connection.requestAirdrop(
+      artist.publicKey,
+      initialBalance.toNumber()
+    );
+    await provider.connection.confirmTransaction(airdrop);
+
+    const artwork = await Token.createMint(
+      provider.connection,
+      artist,
+      artist.publicKey,
+      null,
+      0,
+      TOKEN_PROGRAM_ID
+    );
+    const artistAccount = await artwork.createAccount(artist.publicKey);
+    await artwork.mintTo(artistAccount, artist, [], 1);
+
+    const [exhibition, exhibitionBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("exhibition", "utf8"),
+          mintKeys[indexProposed].publicKey.toBuffer(),
+          new BN(1).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+    const [escrow, escrowBump] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("escrow", "utf8"),
+        mintKeys[indexProposed].publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    const [exhibitionTokenKey, exhibitionTokenBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("token_account", "utf8"),
+          mintKeys[indexProposed].publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+    // Artists approved by the renter can deposit, no exhibitor has to accept
+    // Their pieces are sold once approved by the renter
+    await program.rpc.initializeExhibition(
+      {
+        stead: steadBump,
+        exhibition: exhibitionBump,
+        escrow: escrowBump,
+        exhibitionToken: exhibitionTokenBump,
+      },
+      renterFee,
+      [],
+      new BN(noticePeriod),
+      { approved: {} },
+      true,
+      {
+        accounts: {
+          state: state,
+          stead: stead,
+          exhibition: exhibition,
+          escrow: escrow,
+          exhibitionTokenMint: mintKeys[indexProposed].publicKey,
+          exhibitionTokenAccount: exhibitionTokenKey,
+          renter: renter.publicKey,
+          renterAccount: tokenAccounts[indexProposed],
+          exhibitor: renter.publicKey,
+          payer: provider.wallet.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [renter],
+      }
+    );
+
+    const { status } = await program.account.exhibition.fetch(exhibition);
+    expect("active" in status).to.be.true;
+
+    const [artistApproval, artistApprovalBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("artist", "utf8"),
+          exhibition.toBuffer(),
+          artist.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [depositedTokenKey, depositedTokenBump] =
+      await web3.PublicKey.findProgramAddress(
+        [Buffer.from("token_account", "utf8"), artwork.publicKey.toBuffer()],
+        program.programId
+      );
+    const [exhibitionItemKey, exhibitionItemBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("item", "utf8"),
+          exhibition.toBuffer(),
+          artwork.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const [provenance, provenanceBump] =
+      await web3.PublicKey.findProgramAddress(
+        [Buffer.from("provenance", "utf8"), artwork.publicKey.toBuffer()],
+        program.programId
+      );
+    const [provenanceEntry, provenanceEntryBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("provenance", "utf8"),
+          artwork.publicKey.toBuffer(),
+          new BN(0).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+
+    const bumps = {
+      item: exhibitionItemBump,
+      tokenAccount: depositedTokenBump,
+      provenance: provenanceBump,
+      provenanceEntry: provenanceEntryBump,
+    };
+    const metadata = { caption: "", position: 0, uri: null };
+    const accounts = {
+      state: state,
+      exhibition: exhibition,
+      exhibitionItem: exhibitionItemKey,
+      escrow: escrow,
+      depositedTokenMint: artwork.publicKey,
+      depositedTokenAccount: depositedTokenKey,
+      exhibitor: artist.publicKey,
+      authority: artist.publicKey,
+      delegation: artist.publicKey,
+      provenance: provenance,
+      provenanceEntry: provenanceEntry,
+      exhibitorAccount: artistAccount,
+      payer: provider.wallet.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      rent: SYSVAR_RENT_PUBKEY,
+      systemProgram: SystemProgram.programId,
+    };
+
+    // The artist has not been approved yet
+    await assertError(
+      program.rpc.depositToken(bumps, new BN(10 ** 9), lamports, metadata, [], {
+        accounts,
+        signers: [artist],
+      }),
+      program,
+      "ArtistNotAllowedError"
+    );
+
+    await program.rpc.approveArtist(artistApprovalBump, {
+      accounts: {
+        exhibition: exhibition,
+        artistApproval: artistApproval,
+        artist: artist.publicKey,
+        renter: renter.publicKey,
+        rent: SYSVAR_RENT_PUBKEY,
+        systemProgram: SystemProgram.programId,
+      },
+      signers: [renter],
+    });
+
+    await program.rpc.depositToken(bumps, new BN(10 ** 9), lamports, metadata, [], {
+      accounts,
+      remainingAccounts: [
+        { pubkey: artistApproval, isWritable: false, isSigner: false },
+      ],
+      signers: [artist],
+    });
+
+    const exhibitionItem = await program.account.exhibitionItem.fetch(
+      exhibitionItemKey
+    );
+    expect(exhibitionItem.seller.toString()).to.equal(
+      artist.publicKey.toString()
+    );
+    expect("pending" in exhibitionItem.status).to.be.true;
+
+    const { nPending } = await program.account.exhibition.fetch(exhibition);
+    expect(nPending.toNumber()).to.equal(1);
+
+    // Only the renter curates the exhibition
+    await assertError(
+      program.rpc.approveItem({
+        accounts: {
+          exhibition: exhibition,
+          exhibitionItem: exhibitionItemKey,
+          renter: artist.publicKey,
+        },
+        signers: [artist],
+      }),
+      program,
+      "NotRenterError"
+    );
+
+    await program.rpc.approveItem({
+      accounts: {
+        exhibition: exhibition,
+        exhibitionItem: exhibitionItemKey,
+        renter: renter.publicKey,
+      },
+      signers: [renter],
+    });
+
+    const { status: itemStatus } = await program.account.exhibitionItem.fetch(
+      exhibitionItemKey
+    );
+    expect("listed" in itemStatus).to.be.true;
+
+    const withdrawAccounts = {
+      state: state,
+      exhibition: exhibition,
+      exhibitor: artist.publicKey,
+      authority: artist.publicKey,
+      delegation: artist.publicKey,
+      exhibitorAccount: artistAccount,
+      exhibitionItem: exhibitionItemKey,
+      escrow: escrow,
+      depositedTokenMint: artwork.publicKey,
+      depositedTokenAccount: depositedTokenKey,
+      depositPayer: provider.wallet.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    // The seller lets a manager reprice its pieces
+    const [delegation, delegationBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("delegation", "utf8"),
+          artist.publicKey.toBuffer(),
+          curator.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+    const repricePermission = 1 << 2;
+    await program.rpc.delegate(delegationBump, repricePermission, new BN(0), {
+      accounts: {
+        delegation: delegation,
+        principal: artist.publicKey,
+        manager: curator.publicKey,
+        rent: SYSVAR_RENT_PUBKEY,
+        systemProgram: SystemProgram.programId,
+      },
+      signers: [artist],
+    });
+
+    await program.rpc.setItemPrice(new BN(2 * 10 ** 9), lamports, {
+      accounts: {
+        exhibitionItem: exhibitionItemKey,
+        exhibitor: artist.publicKey,
+        authority: curator.publicKey,
+        delegation: delegation,
+      },
+      signers: [curator],
+    });
+
+    const { price } = await program.account.exhibitionItem.fetch(
+      exhibitionItemKey
+    );
+    expect(price.toNumber()).to.equal(2 * 10 ** 9);
+
+    // Only the seller, or a manager allowed to, can withdraw it
+    await assertError(
+      program.rpc.withdrawToken(
+        { tokenAccount: depositedTokenBump },
+        {
+          accounts: {
+            ...withdrawAccounts,
+            authority: curator.publicKey,
+            delegation: delegation,
+          },
+          signers: [curator],
+        }
+      ),
+      program,
+      "NotDelegatedError"
+    );
+    await assertError(
+      program.rpc.withdrawToken(
+        { tokenAccount: depositedTokenBump },
+        {
+          accounts: { ...withdrawAccounts, authority: renter.publicKey },
+          signers: [renter],
+        }
+      ),
+      program,
+      "NotDelegatedError"
+    );
+
+    await program.rpc.revokeDelegation({
+      accounts: {
+        delegation: delegation,
+        principal: artist.publicKey,
+      },
+      signers: [artist],
+    });
+
+    await program.rpc.withdrawToken(
+      { tokenAccount: depositedTokenBump },
+      { accounts: withdrawAccounts, signers: [artist] }
+    );
+
+    const artistTokenAccount = await artwork.getAccountInfo(artistAccount);
+    expect(artistTokenAccount.amount.toNumber()).to.equal(1);
+
+    const [secondEntry, secondEntryBump] =
+      await web3.PublicKey.findProgramAddress(
+        [
+          Buffer.from("provenance", "utf8"),
+          artwork.publicKey.toBuffer(),
+          new BN(1).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+    await program.rpc.depositToken(
+      { ...bumps, provenanceEntry: secondEntryBump },
+      new BN(10 ** 9),
+      lamports,
+      metadata,
+      [],
+      {
+        accounts: { ...accounts, provenanceEntry: secondEntry },
+        remainingAccounts: [
+          { pubkey: artistApproval, isWritable: false, isSigner: false },
+        ],
+        signers: [artist],
+      }
+    );
+
+    // The renter turns the piece down
+    const artistAssociatedAccount = await findAssociatedAddress(
+      artist.publicKey,
+      artwork.publicKey
+    );
+    await program.rpc.rejectItem(
+      { tokenAccount: depositedTokenBump },
+      {
+        accounts: {
+          state: state,
+          exhibition: exhibition,
+          exhibitor: artist.publicKey,
+          exhibitorAccount: artistAssociatedAccount,
+          exhibitionItem: exhibitionItemKey,
+          escrow: escrow,
+          depositedTokenMint: artwork.publicKey,
+          depositedTokenAccount: depositedTokenKey,
+          depositPayer: provider.wallet.publicKey,
+          renter: renter.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+        signers: [renter],
+      }
+    );
+
+    const rejectedAccount = await artwork.getAccountInfo(
+      artistAssociatedAccount
+    );
+    expect(rejectedAccount.amount.toNumber()).to.equal(1);
+    expect(
+      await provider.connection.getAccountInfo(exhibitionItemKey)
+    ).to.equal(null);
+
+    const { nPieces, nPending: nPendingLeft } =
+      await program.account.exhibition.fetch(exhibition);
+    expect(nPieces.toNumber()).to.equal(0);
+    expect(nPendingLeft.toNumber()).to.equal(0);
+
+    await program.rpc.revokeArtist({
+      accounts: {
+        exhibition: exhibition,
+        artistApproval: artistApproval,
+        renter: renter.publicKey,
+      },
+      signers: [renter],
+    });
+
+    expect(
+      await provider.connection.getAccountInfo(artistApproval)
+    ).to.equal(null);
+  });
+
+  it("Governs the state with admins", async () => {
+    const wallet = provider.wallet.publicKey;
+    const admins = [wallet, dao.publicKey, otherDao.publicKey];
+
+    const findProposal = (index: number) =>
+      PublicKey.findProgramAddress(
+        [Buffer.from("proposal", "utf8"), new BN(index).toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+    const createProposal = async (action: any) => {
+      const { nProposals } = await program.account.state.fetch(state);
+      const [proposal, bump] = await findProposal(nProposals.toNumber());
+      await program.rpc.createProposal(bump, action, {
+        accounts: {
+          state: state,
+          proposal: proposal,
+          proposer: wallet,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        },
+      });
+      return proposal;
+    };
+    const approveProposal = (proposal: PublicKey, admin: Keypair) =>
+      program.rpc.approveProposal({
+        accounts: { state: state, proposal: proposal, admin: admin.publicKey },
+        signers: [admin],
+      });
+    const executeProposal = (proposal: PublicKey) =>
+      program.rpc.executeProposal({
+        accounts: { state: state, proposal: proposal, proposer: wallet },
+      });
+
+    // Only the fee earner hands the state over
+    await assertError(
+      program.rpc.initializeMultisig(admins, 2, new BN(0), {
+        accounts: { state: state, owner: otherDao.publicKey },
+        signers: [otherDao],
+      }),
+      program,
+      "NotFeeEarnerError"
+    );
+
+    // The threshold must be reachable and admins distinct
+    await assertError(
+      program.rpc.initializeMultisig(admins, 4, new BN(0), {
+        accounts: { state: state, owner: dao.publicKey },
+        signers: [dao],
+      }),
+      program,
+      "InvalidAdminsError"
+    );
+    await assertError(
+      program.rpc.initializeMultisig([wallet, wallet], 1, new BN(0), {
+        accounts: { state: state, owner: dao.publicKey },
+        signers: [dao],
+      }),
+      program,
+      "InvalidAdminsError"
+    );
+
+    await program.rpc.initializeMultisig(admins, 2, new BN(0), {
+      accounts: { state: state, owner: dao.publicKey },
+      signers: [dao],
+    });
+
+    let s = await program.account.state.fetch(state);
+    expect(s.admins.map((admin) => admin.toString())).to.deep.equal(
+      admins.map((admin) => admin.toString())
+    );
+    expect(s.threshold).to.equal(2);
+
+    // The fee earner alone can no longer change the state
+    await assertError(
+      program.rpc.setState(
+        dao.publicKey,
+        feeAmount,
+        referralFee,
+        new BN(0),
+        null,
+        false,
+        {
+          accounts: { state: state, owner: dao.publicKey },
+          signers: [dao],
+        }
+      ),
+      program,
+      "StateGovernedError"
+    );
+
+    // Only admins propose changes
+    const [nextProposal, nextBump] = await findProposal(0);
+    await assertError(
+      program.rpc.createProposal(
+        nextBump,
+        { setPaused: { paused: true } },
+        {
+          accounts: {
+            state: state,
+            proposal: nextProposal,
+            proposer: renter.publicKey,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [renter],
+        }
+      ),
+      program,
+      "NotAdminError"
+    );
+
+    // Pausing takes the approval of a second admin
+    const pause = await createProposal({ setPaused: { paused: true } });
+    await assertError(executeProposal(pause), program, "ThresholdNotReachedError");
+    await assertError(
+      approveProposal(pause, renter),
+      program,
+      "NotAdminError"
+    );
+    await approveProposal(pause, dao);
+    await assertError(
+      approveProposal(pause, dao),
+      program,
+      "AlreadyApprovedError"
+    );
+    await executeProposal(pause);
+
+    s = await program.account.state.fetch(state);
+    expect(s.paused).to.be.true;
+    expect(await provider.connection.getAccountInfo(pause)).to.equal(null);
+
+    // No exhibition opens while the program is paused
+    const property = await Token.createMint(
+      provider.connection,
+      renter,
+      renter.publicKey,
+      null,
+      0,
+      TOKEN_PROGRAM_ID
+    );
+    const renterAccount = await property.createAccount(renter.publicKey);
+    await property.mintTo(renterAccount, renter, [], 1);
+    const find = (...seeds: Buffer[]) =>
+      PublicKey.findProgramAddress(seeds, program.programId);
+    const [stead, steadBump] = await find(
+      Buffer.from("stead", "utf8"),
+      property.publicKey.toBuffer()
+    );
+    const [exhibition, exhibitionBump] = await find(
+      Buffer.from("exhibition", "utf8"),
+      property.publicKey.toBuffer(),
+      new BN(0).toArrayLike(Buffer, "le", 8)
+    );
+    const [escrow, escrowBump] = await find(
+      Buffer.from("escrow", "utf8"),
+      property.publicKey.toBuffer()
+    );
+    const [exhibitionToken, exhibitionTokenBump] = await find(
+      Buffer.from("token_account", "utf8"),
+      property.publicKey.toBuffer()
+    );
+    await assertError(
+      program.rpc.initializeExhibition(
+        {
+          stead: steadBump,
+          exhibition: exhibitionBump,
+          escrow: escrowBump,
+          exhibitionToken: exhibitionTokenBump,
+        },
+        renterFee,
+        [],
+        new BN(noticePeriod),
+        { exhibitor: {} },
+        false,
+        {
+          accounts: {
+            state: state,
+            stead: stead,
+            exhibition: exhibition,
+            escrow: escrow,
+            exhibitionTokenMint: property.publicKey,
+            exhibitionTokenAccount: exhibitionToken,
+            renter: renter.publicKey,
+            renterAccount: renterAccount,
+            exhibitor: exhibitor.publicKey,
+            payer: provider.wallet.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY,
+            systemProgram: SystemProgram.programId,
+          },
+          signers: [renter],
+        }
+      ),
+      program,
+      "PausedError"
+    );
+
+    const resume = await createProposal({ setPaused: { paused: false } });
+    await approveProposal(resume, otherDao);
+    await executeProposal(resume);
+
+    // Admins restrict deposits to some collections
+    await assertError(
+      program.rpc.setAllowedCollections([dao.publicKey], {
+        accounts: { state: state, owner: dao.publicKey },
+        signers: [dao],
+      }),
+      program,
+      "StateGovernedError"
+    );
+    const collections = await createProposal({
+      setAllowedCollections: { collections: [dao.publicKey] },
+    });
+    await approveProposal(collections, otherDao);
+    await executeProposal(collections);
+
+    s = await program.account.state.fetch(state);
+    expect(
+      s.allowedCollections.map((collection) => collection.toString())
+    ).to.deep.equal([dao.publicKey.toString()]);
+
+    // Changes can wait for a timelock once approved
+    const timelock = await createProposal({
+      setAdmins: { admins: admins, threshold: 2, timelock: new BN(3600) },
+    });
+    await approveProposal(timelock, dao);
+    await executeProposal(timelock);
+
+    const fees = await createProposal({
+      setFees: {
+        feeAmount: feeAmount * 2,
+        referralFee: referralFee,
+        minFee: new BN(0),
+        maxFee: null,
+      },
+    });
+    await approveProposal(fees, otherDao);
+    await assertError(executeProposal(fees), program, "TimelockError");
+
+    s = await program.account.state.fetch(state);
+    expect(s.paused).to.be.false;
+    expect(s.timelock.toNumber()).to.equal(3600);
+    expect(s.feeAmount).to.equal(feeAmount);
+
+    const { executableAt, createdAt } = await program.account.proposal.fetch(
+      fees
+    );
+    expect(executableAt.toNumber()).to.be.greaterThan(
+      Date.now() / 1000 + 3000
+    );
+    expect(createdAt.toNumber()).to.be.lessThan(executableAt.toNumber());
+
+    // Only the proposer withdraws a proposal before it expires
+    const cancelAccounts = {
+      state: state,
+      proposal: fees,
+      proposer: wallet,
+      authority: dao.publicKey,
+    };
+    await assertError(
+      program.rpc.cancelProposal({
+        accounts: cancelAccounts,
+        signers: [dao],
+      }),
+      program,
+      "NotProposerError"
+    );
+    await program.rpc.cancelProposal({
+      accounts: { ...cancelAccounts, authority: wallet },
+    });
+    expect(await provider.connection.getAccountInfo(fees)).to.equal(null);
   });
 });